@@ -0,0 +1,262 @@
+use crate::error::{Result, VanityError};
+use crate::search::search_vanity;
+use crate::searcher::Searcher;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Versión del formato del fichero de calibración
+const CALIBRATION_VERSION: u32 = 1;
+
+/// Variable de entorno con una ruta alternativa para la calibración
+pub const CALIBRATION_ENV: &str = "TORGE_FORGE_CALIBRATION";
+
+/// Tasa supuesta (intentos/s) cuando la máquina no está calibrada
+pub const ASSUMED_RATE: f64 = 300_000.0;
+
+/// Prefijo que no aparece durante una medición (probabilidad 2^-60)
+const UNREACHABLE_PREFIX: &str = "aaaaaaaaaaaa";
+
+/// Tasa medida con un número de hilos
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BenchPoint {
+    /// Número de hilos
+    pub threads: usize,
+    /// Intentos por segundo
+    pub rate: f64,
+}
+
+/// Tasas de esta máquina guardadas por `torge-forge-cli bench`
+///
+/// Las estimaciones de tiempo la usan automáticamente en lugar de
+/// `ASSUMED_RATE`; entre dos puntos medidos se interpola.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    /// Versión del formato
+    pub version: u32,
+    /// Fecha de la medición (RFC 3339)
+    pub measured_at: String,
+    /// Duración de cada medición en segundos
+    pub seconds_per_point: f64,
+    /// Puntos medidos, ordenados por número de hilos
+    pub points: Vec<BenchPoint>,
+}
+
+impl Calibration {
+    /// Crea una calibración con los puntos medidos
+    pub fn new(mut points: Vec<BenchPoint>, seconds_per_point: f64) -> Self {
+        points.sort_by_key(|point| point.threads);
+        Self {
+            version: CALIBRATION_VERSION,
+            measured_at: chrono::Utc::now().to_rfc3339(),
+            seconds_per_point,
+            points,
+        }
+    }
+
+    /// Tasa esperada con un número de hilos
+    ///
+    /// Entre dos puntos se interpola linealmente; por debajo del primero se
+    /// escala en proporción a los hilos y por encima del último se mantiene
+    /// la última tasa (más hilos que núcleos no aceleran).
+    ///
+    /// # Arguments
+    ///
+    /// * `threads` - Número de hilos de la búsqueda
+    ///
+    /// # Returns
+    ///
+    /// Intentos por segundo, o None si no hay puntos
+    pub fn rate_for(&self, threads: usize) -> Option<f64> {
+        let first = self.points.first()?;
+        let last = self.points.last()?;
+        if threads <= first.threads {
+            return Some(first.rate * threads as f64 / first.threads as f64);
+        }
+        if threads >= last.threads {
+            return Some(last.rate);
+        }
+
+        let upper = self.points.iter().position(|p| p.threads >= threads)?;
+        let (low, high) = (self.points[upper - 1], self.points[upper]);
+        let fraction = (threads - low.threads) as f64 / (high.threads - low.threads) as f64;
+        Some(low.rate + (high.rate - low.rate) * fraction)
+    }
+
+    /// Ruta por defecto: `$TORGE_FORGE_CALIBRATION` o el directorio de
+    /// configuración del usuario (`~/.config/torge-forge/calibration.json`)
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(CALIBRATION_ENV) {
+            return Some(PathBuf::from(path));
+        }
+
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+        Some(config_dir.join("torge-forge").join("calibration.json"))
+    }
+
+    /// Carga una calibración
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Fichero de calibración
+    ///
+    /// # Returns
+    ///
+    /// La calibración, o un error si no existe o no es válida (incluido un
+    /// punto sin hilos o con una tasa que no sea un número positivo)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            VanityError::InvalidConfig(format!(
+                "No se pudo leer la calibración {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let mut calibration: Self = serde_json::from_str(&content)?;
+        if calibration.version != CALIBRATION_VERSION {
+            return Err(VanityError::InvalidConfig(format!(
+                "Versión de calibración no soportada: {}",
+                calibration.version
+            )));
+        }
+        if let Some(point) = calibration
+            .points
+            .iter()
+            .find(|point| point.threads == 0 || !point.rate.is_finite() || point.rate <= 0.0)
+        {
+            return Err(VanityError::InvalidConfig(format!(
+                "Punto de calibración inválido en {}: {} hilo(s) a {} intentos/s",
+                path.display(),
+                point.threads,
+                point.rate
+            )));
+        }
+        calibration.points.sort_by_key(|point| point.threads);
+        Ok(calibration)
+    }
+
+    /// Guarda la calibración de forma atómica, creando el directorio si falta
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Tasa calibrada de esta máquina para un número de hilos
+///
+/// # Returns
+///
+/// None si no hay calibración en la ruta por defecto o no se puede leer
+pub fn machine_rate(threads: usize) -> Option<f64> {
+    let path = Calibration::default_path()?;
+    Calibration::load(&path).ok()?.rate_for(threads)
+}
+
+/// Números de hilos a medir: potencias de dos hasta `max_threads`, y este
+pub fn bench_thread_counts(max_threads: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> = std::iter::successors(Some(1usize), |n| n.checked_mul(2))
+        .take_while(|&n| n < max_threads)
+        .collect();
+    counts.push(max_threads.max(1));
+    counts
+}
+
+/// Mide la tasa de búsqueda con un número de hilos
+///
+/// Busca un prefijo inalcanzable durante `duration` con el mismo camino que
+/// una búsqueda real (recorrido de claves, lotes y matcher de prefijos).
+///
+/// # Arguments
+///
+/// * `threads` - Número de hilos
+/// * `duration` - Duración de la medición
+///
+/// # Returns
+///
+/// Intentos por segundo
+pub fn measure_rate(threads: usize, duration: Duration) -> Result<f64> {
+    let config = Searcher::new()
+        .prefix(UNREACHABLE_PREFIX)
+        .threads(threads)
+        .max_time(duration)
+        .config()?;
+
+    let stats = search_vanity(&config, |_| Ok(()))?;
+    Ok(stats.rate())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn point(threads: usize, rate: f64) -> BenchPoint {
+        BenchPoint { threads, rate }
+    }
+
+    #[test]
+    fn test_rate_for() {
+        let calibration = Calibration::new(
+            vec![point(4, 350.0), point(1, 100.0), point(2, 190.0)],
+            1.0,
+        );
+        assert_eq!(calibration.points[0].threads, 1);
+
+        assert_eq!(calibration.rate_for(2), Some(190.0));
+        assert_eq!(calibration.rate_for(3), Some(270.0));
+        assert_eq!(calibration.rate_for(16), Some(350.0));
+
+        let calibration = Calibration::new(vec![point(2, 200.0)], 1.0);
+        assert_eq!(calibration.rate_for(1), Some(100.0));
+        assert_eq!(Calibration::new(Vec::new(), 1.0).rate_for(4), None);
+    }
+
+    #[test]
+    fn test_bench_thread_counts() {
+        assert_eq!(bench_thread_counts(1), vec![1]);
+        assert_eq!(bench_thread_counts(4), vec![1, 2, 4]);
+        assert_eq!(bench_thread_counts(6), vec![1, 2, 4, 6]);
+        assert_eq!(bench_thread_counts(0), vec![1]);
+    }
+
+    #[test]
+    fn test_calibration_save_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("calibration.json");
+
+        let rate = measure_rate(1, Duration::from_millis(100)).unwrap();
+        assert!(rate > 0.0);
+
+        let calibration = Calibration::new(vec![point(1, rate)], 0.1);
+        calibration.save(&path).unwrap();
+        assert_eq!(Calibration::load(&path).unwrap(), calibration);
+
+        fs::write(&path, r#"{"version":99,"measured_at":"","seconds_per_point":1,"points":[]}"#)
+            .unwrap();
+        assert!(Calibration::load(&path).is_err());
+
+        // Puntos con los que rate_for dividiría por cero o daría tasas absurdas
+        for points in [
+            r#"[{"threads":0,"rate":100.0}]"#,
+            r#"[{"threads":1,"rate":0.0}]"#,
+            r#"[{"threads":1,"rate":-5.0}]"#,
+        ] {
+            let content = format!(
+                r#"{{"version":1,"measured_at":"","seconds_per_point":1,"points":{}}}"#,
+                points
+            );
+            fs::write(&path, content).unwrap();
+            assert!(Calibration::load(&path).is_err());
+        }
+    }
+}
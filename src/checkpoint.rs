@@ -0,0 +1,248 @@
+use crate::error::{Result, VanityError};
+use crate::types::{Config, PatternStats, Quota};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Nombre del fichero de checkpoint dentro de `output_dir`
+pub const CHECKPOINT_FILE: &str = "torge-forge.checkpoint.json";
+
+/// Versión del formato del checkpoint
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// Descripción de la búsqueda guardada en el checkpoint
+///
+/// Sirve para comprobar al reanudar que la configuración es la misma; el
+/// matcher no es serializable, así que se guarda su nombre.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointSearch {
+    /// Nombre del matcher (`Matcher::name`)
+    pub matcher: String,
+    /// Prefijos buscados
+    pub prefixes: Vec<String>,
+    /// Cupos por patrón
+    pub quotas: Vec<Quota>,
+    /// Número máximo de resultados
+    pub max_results: usize,
+    /// Número máximo de intentos (acumulado entre sesiones)
+    pub max_attempts: Option<u64>,
+    /// Nombre de la fuente de claves (`KeySource::name`)
+    #[serde(default)]
+    pub key_source: String,
+    /// Número de workers (cada uno con su posición si la fuente es determinista)
+    #[serde(default)]
+    pub threads: usize,
+}
+
+impl CheckpointSearch {
+    /// Describe la búsqueda de una configuración
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            matcher: config.matcher.name().to_string(),
+            prefixes: config.prefixes.clone(),
+            quotas: config.quotas.clone(),
+            max_results: config.max_results,
+            max_attempts: config.max_attempts,
+            key_source: config.key_source.name(),
+            threads: config.threads,
+        }
+    }
+}
+
+/// Estado acumulado de una búsqueda larga
+///
+/// `search_vanity` lo escribe periódicamente en `output_dir`; con `--resume`
+/// la búsqueda continúa sumando intentos, tiempo y resultados a los guardados.
+/// Con la fuente de claves del sistema no hay posiciones que guardar: al
+/// reanudar se empieza en puntos nuevos, lo que no cambia la probabilidad de
+/// éxito por intento. Con una fuente determinista se guarda el paso de cada
+/// worker y la búsqueda continúa exactamente donde se quedó.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Versión del formato
+    pub version: u32,
+    /// Búsqueda a la que pertenece
+    pub search: CheckpointSearch,
+    /// Intentos acumulados
+    pub total_attempts: u64,
+    /// Tiempo de búsqueda acumulado en segundos
+    pub elapsed_seconds: f64,
+    /// Resultados aceptados hasta ahora
+    pub results_found: usize,
+    /// Progreso por patrón
+    pub patterns: Vec<PatternStats>,
+    /// Paso de cada worker (solo con fuentes de claves deterministas)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workers: Vec<u64>,
+    /// Recorrido de cada worker, que cambia tras cada acierto (solo con
+    /// fuentes deterministas; los checkpoints sin él están en el primero)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<u64>,
+    /// Fecha de la última escritura (RFC 3339)
+    pub updated_at: String,
+}
+
+impl Checkpoint {
+    /// Crea un checkpoint vacío para una configuración
+    pub fn new(config: &Config) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            search: CheckpointSearch::from_config(config),
+            total_attempts: 0,
+            elapsed_seconds: 0.0,
+            results_found: 0,
+            patterns: Vec::new(),
+            workers: Vec::new(),
+            segments: Vec::new(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Ruta del checkpoint dentro de un directorio de salida
+    pub fn path(output_dir: &Path) -> PathBuf {
+        output_dir.join(CHECKPOINT_FILE)
+    }
+
+    /// Carga el checkpoint de un directorio de salida
+    ///
+    /// # Arguments
+    ///
+    /// * `output_dir` - Directorio de salida de la búsqueda
+    ///
+    /// # Returns
+    ///
+    /// El checkpoint leído, o un error si no existe o no es válido
+    pub fn load(output_dir: &Path) -> Result<Self> {
+        let path = Self::path(output_dir);
+        let content = fs::read_to_string(&path).map_err(|e| {
+            VanityError::InvalidConfig(format!(
+                "No se pudo leer el checkpoint {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let checkpoint: Self = serde_json::from_str(&content)?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(VanityError::InvalidConfig(format!(
+                "Versión de checkpoint no soportada: {}",
+                checkpoint.version
+            )));
+        }
+        Ok(checkpoint)
+    }
+
+    /// Guarda el checkpoint de forma atómica (fichero temporal + rename)
+    pub fn save(&mut self, output_dir: &Path) -> Result<()> {
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+
+        let path = Self::path(output_dir);
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// Elimina el checkpoint de un directorio (si existe)
+    pub fn remove(output_dir: &Path) -> Result<()> {
+        match fs::remove_file(Self::path(output_dir)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Comprueba que el checkpoint pertenece a la búsqueda configurada
+    pub fn check_config(&self, config: &Config) -> Result<()> {
+        let current = CheckpointSearch::from_config(config);
+        let saved = &self.search;
+
+        if saved.matcher != current.matcher
+            || saved.prefixes != current.prefixes
+            || saved.quotas != current.quotas
+        {
+            return Err(VanityError::InvalidConfig(format!(
+                "El checkpoint corresponde a otra búsqueda ({}), no a {}",
+                saved.matcher, current.matcher
+            )));
+        }
+
+        // Las posiciones guardadas solo valen para la misma semilla
+        if config.key_source.is_deterministic() && saved.key_source != current.key_source {
+            return Err(VanityError::InvalidConfig(format!(
+                "El checkpoint se generó con otra fuente de claves ({}), no con {}",
+                saved.key_source, current.key_source
+            )));
+        }
+
+        // Cada worker continúa su propio recorrido: con otro número de hilos
+        // se perderían posiciones o habría workers empezando desde el paso 0
+        // (los checkpoints sin `threads` guardan una posición por worker)
+        let saved_threads = match saved.threads {
+            0 => self.workers.len(),
+            threads => threads,
+        };
+        if config.key_source.is_deterministic() && saved_threads != current.threads {
+            return Err(VanityError::InvalidConfig(format!(
+                "El checkpoint se generó con {} hilo(s): reanude con --threads {}",
+                saved_threads, saved_threads
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_source::SeededKeySource;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn config(prefixes: &[&str]) -> Config {
+        Config::for_test(prefixes)
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut checkpoint = Checkpoint::new(&config(&["abc"]));
+        checkpoint.total_attempts = 123_456;
+        checkpoint.elapsed_seconds = 42.5;
+
+        checkpoint.save(temp_dir.path()).unwrap();
+        let loaded = Checkpoint::load(temp_dir.path()).unwrap();
+        assert_eq!(loaded, checkpoint);
+
+        Checkpoint::remove(temp_dir.path()).unwrap();
+        assert!(Checkpoint::load(temp_dir.path()).is_err());
+        Checkpoint::remove(temp_dir.path()).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint_config_mismatch() {
+        let checkpoint = Checkpoint::new(&config(&["abc"]));
+        assert!(checkpoint.check_config(&config(&["abc"])).is_ok());
+        assert!(checkpoint.check_config(&config(&["xyz"])).is_err());
+
+        // Con una fuente determinista la semilla también debe coincidir
+        let seeded = |seed| Config {
+            key_source: Arc::new(SeededKeySource::new(seed)),
+            ..config(&["abc"])
+        };
+        let checkpoint = Checkpoint::new(&seeded(1));
+        assert!(checkpoint.check_config(&seeded(1)).is_ok());
+        assert!(checkpoint.check_config(&seeded(2)).is_err());
+
+        // ... y el número de hilos, porque las posiciones son por worker
+        let threads = Config {
+            threads: 3,
+            ..seeded(1)
+        };
+        assert!(checkpoint.check_config(&threads).is_err());
+        let threads = Config {
+            threads: 3,
+            ..config(&["abc"])
+        };
+        assert!(Checkpoint::new(&config(&["abc"])).check_config(&threads).is_ok());
+    }
+}
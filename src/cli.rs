@@ -1,6 +1,27 @@
-use crate::types::Config;
-use clap::Parser;
-use std::path::PathBuf;
+use vanity_onion_v3::checkpoint::Checkpoint;
+use vanity_onion_v3::difficulty::{Goal, PrefixSet};
+use vanity_onion_v3::dictionary::{expand_leet, leet_lookalikes, load_dictionary};
+use vanity_onion_v3::distributed::{load_cluster_key, Job, WorkerOptions};
+use vanity_onion_v3::generator::{base32_value, explain_impossible_suffix};
+use vanity_onion_v3::key_source::{OsKeySource, SeededKeySource};
+use vanity_onion_v3::matcher::{
+    AnyMatcher, DictionaryMatcher, Matcher, PrefixMatcher, RegexMatcher, SuffixMatcher,
+};
+use vanity_onion_v3::reporter::{ReportOptions, ReportStyle};
+use vanity_onion_v3::scoring::{
+    FewestDigitsScorer, LongestPrefixScorer, PronounceableScorer, ScoreMatcher, Scorer,
+};
+use vanity_onion_v3::search::{deadline_at, format_duration, parse_duration};
+use vanity_onion_v3::split_key::{SplitJob, OFFSETS_FILE};
+use vanity_onion_v3::types::{CancelToken, Config, Quota};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Número máximo de prefijos cubiertos que se advierten uno a uno
+const LISTED_COVERED_PREFIXES: usize = 5;
 
 /// Generador de direcciones .onion v3 vanity para servicios ocultos de Tor
 #[derive(Parser, Debug)]
@@ -8,15 +29,75 @@ use std::path::PathBuf;
 #[command(author = "Your Name")]
 #[command(version = "0.1.0")]
 #[command(about = "Genera direcciones .onion v3 con prefijos personalizados", long_about = None)]
+#[command(subcommand_negates_reqs = true)]
 pub struct Cli {
+    /// Operaciones de clave dividida (sin subcomando se hace una búsqueda normal)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Prefijo(s) a buscar (puede especificarse múltiples veces)
     /// 
     /// Los prefijos deben usar solo caracteres válidos en base32: a-z y 2-7
     /// 
     /// Ejemplo: --prefix ctec --prefix nahum
-    #[arg(short, long = "prefix", required = true)]
+    #[arg(
+        short,
+        long = "prefix",
+        required_unless_present_any = ["prefix_file", "dictionary", "suffixes", "regexes", "top", "quotas", "worker", "split_job"]
+    )]
     pub prefixes: Vec<String>,
 
+    /// Admite 0, 1, 8 y 9 en los prefijos expandiéndolos a letras parecidas
+    ///
+    /// 0→o, 1→l/i, 8→b, 9→g: "b10g" busca a la vez "blog" y "biog", y el
+    /// resultado indica cuál se encontró.
+    ///
+    /// Ejemplo: --prefix c0ffee --leet
+    #[arg(long, default_value_t = false)]
+    pub leet: bool,
+
+    /// Fichero con prefijos a buscar, uno por línea
+    ///
+    /// Pensado para listas grandes (miles de marcas o palabras): la búsqueda
+    /// usa un trie y su coste por intento no depende del número de prefijos.
+    /// Se ignoran las líneas vacías y las que empiezan por '#'.
+    ///
+    /// Ejemplo: --prefix-file marcas.txt
+    #[arg(long)]
+    pub prefix_file: Option<PathBuf>,
+
+    /// Diccionario (una palabra por línea): acepta cualquier dirección que
+    /// empiece por una de sus palabras
+    ///
+    /// Las palabras se normalizan a base32: se quitan los acentos ("camión" →
+    /// "camion") y se descartan las que contienen 0, 1, 8, 9 u otros símbolos.
+    ///
+    /// Ejemplo: --dictionary /usr/share/dict/spanish --min-word-length 5
+    #[arg(short, long)]
+    pub dictionary: Option<PathBuf>,
+
+    /// Longitud mínima de las palabras del diccionario (tras normalizar)
+    #[arg(long, default_value_t = 4, requires = "dictionary")]
+    pub min_word_length: usize,
+
+    /// Sufijo(s) a buscar al final de la dirección, antes de ".onion"
+    ///
+    /// Las direcciones v3 siempre terminan en 'd' y el penúltimo carácter solo
+    /// puede ser a, i, q o y (byte de versión); los sufijos imposibles se descartan.
+    ///
+    /// Ejemplo: --suffix xyd
+    #[arg(short, long = "suffix")]
+    pub suffixes: Vec<String>,
+
+    /// Expresión(es) regular(es) sobre los 56 caracteres de la dirección
+    ///
+    /// Solo pueden coincidir caracteres a-z y 2-7; los patrones imposibles
+    /// (por ejemplo, que no terminen en 'd' si se anclan con $) se rechazan.
+    ///
+    /// Ejemplo: --regex '^(shop|store)[a-z]{2}'
+    #[arg(short, long = "regex")]
+    pub regexes: Vec<String>,
+
     /// Número de hilos a utilizar (por defecto: número de CPUs lógicas)
     #[arg(short, long, default_value_t = num_cpus::get())]
     pub threads: usize,
@@ -25,10 +106,37 @@ pub struct Cli {
     #[arg(short = 'n', long, default_value_t = 1)]
     pub max_results: usize,
 
+    /// Cupos de resultados por prefijo, con formato prefijo:n
+    ///
+    /// Cada prefijo se deja de buscar al cubrir su cupo y la búsqueda termina
+    /// cuando todos están cubiertos. Los prefijos de los cupos se añaden a la
+    /// búsqueda (con --leet, el cupo vale para cada variante); los demás
+    /// prefijos comparten --max-results y no retienen la búsqueda.
+    ///
+    /// Ejemplo: --quotas shop:3,blog:1
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["dictionary", "suffixes", "regexes", "top"]
+    )]
+    pub quotas: Vec<Quota>,
+
     /// Número máximo de intentos antes de detenerse (opcional, sin límite por defecto)
     #[arg(short = 'a', long)]
     pub max_attempts: Option<u64>,
 
+    /// Detener la búsqueda tras este tiempo (unidades d, h, m y s)
+    ///
+    /// Ejemplo: --max-time 6h, --max-time 1h30m
+    #[arg(long, value_name = "DURACIÓN", value_parser = parse_duration, conflicts_with = "until")]
+    pub max_time: Option<Duration>,
+
+    /// Detener la búsqueda a esta hora (RFC 3339), p. ej. al acabar una ventana nocturna
+    ///
+    /// Ejemplo: --until 2025-06-01T06:00:00+02:00
+    #[arg(long, value_name = "FECHA", value_parser = parse_until)]
+    pub until: Option<DateTime<Utc>>,
+
     /// Directorio de salida para guardar las claves generadas
     #[arg(short, long, default_value = "./output")]
     pub output_dir: PathBuf,
@@ -37,10 +145,41 @@ pub struct Cli {
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
 
-    /// Mostrar estadísticas de progreso cada N segundos
+    /// Continuar la búsqueda guardada en el checkpoint del directorio de salida
+    ///
+    /// Los intentos, el tiempo y los resultados acumulados se suman a los de
+    /// esta ejecución; la búsqueda debe tener los mismos patrones.
+    #[arg(long, default_value_t = false, conflicts_with = "dry_run")]
+    pub resume: bool,
+
+    /// Guardar el checkpoint cada N segundos (0 = no guardar checkpoints)
+    #[arg(long, default_value_t = 60)]
+    pub checkpoint_interval: u64,
+
+    /// ⚠️ SOLO PARA PRUEBAS: generar las claves con ChaCha20 a partir de esta semilla
+    ///
+    /// La misma semilla y el mismo número de hilos reproducen exactamente la
+    /// búsqueda. Cualquiera que conozca la semilla puede regenerar las claves
+    /// privadas, así que solo se admite con --dry-run salvo que se indique
+    /// --insecure-allow-seeded-keys.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Permitir guardar claves generadas con --seed (inseguras, nunca en producción)
+    #[arg(long, default_value_t = false, requires = "seed")]
+    pub insecure_allow_seeded_keys: bool,
+
+    /// Mostrar estadísticas de progreso cada N segundos (0 = no mostrarlas)
+    ///
+    /// Incluyen la tasa actual, el tiempo esperado hasta el siguiente acierto
+    /// de cada prefijo y la probabilidad de haberlo encontrado ya.
     #[arg(long, default_value_t = 10)]
     pub stats_interval: u64,
 
+    /// Mostrar el progreso en una sola línea que se redibuja en el sitio
+    #[arg(long, default_value_t = false, conflicts_with = "quiet")]
+    pub compact_stats: bool,
+
     /// Modo silencioso: no mostrar estadísticas de progreso
     #[arg(short, long, default_value_t = false)]
     pub quiet: bool,
@@ -48,26 +187,422 @@ pub struct Cli {
     /// Mostrar estimación de tiempo para cada prefijo
     #[arg(long, default_value_t = false)]
     pub estimate: bool,
+
+    /// Modo puntuación: conservar las N direcciones mejor puntuadas
+    ///
+    /// En lugar de parar en el primer acierto, busca durante el presupuesto
+    /// (--max-attempts, --max-time o --until) y guarda solo las N ganadoras.
+    ///
+    /// Ejemplo: --top 5 --scorer pronounceable --max-time 10m
+    #[arg(long, conflicts_with_all = ["dictionary", "suffixes", "regexes"])]
+    pub top: Option<usize>,
+
+    /// Criterio de puntuación del modo --top
+    #[arg(long, value_enum, default_value_t = ScorerKind::LongestPrefix, requires = "top")]
+    pub scorer: ScorerKind,
+
+    /// Coordinar una búsqueda distribuida escuchando en esta dirección
+    ///
+    /// Los workers (--worker) reciben los prefijos, sufijos y expresiones
+    /// regulares; el coordinador verifica y guarda sus resultados y los
+    /// detiene a todos al llegar a --max-results, --max-attempts, --max-time
+    /// o --until.
+    ///
+    /// Ejemplo: --coordinator 0.0.0.0:7878 --cluster-key-file cluster.key --prefix torge
+    #[arg(
+        long,
+        value_name = "ADDR",
+        requires = "cluster_key_file",
+        conflicts_with_all = ["worker", "dictionary", "top", "quotas", "resume", "seed"]
+    )]
+    pub coordinator: Option<String>,
+
+    /// Trabajar para el coordinador de esta dirección (la búsqueda la decide él)
+    ///
+    /// Si la conexión se pierde, el worker se reconecta solo.
+    ///
+    /// Ejemplo: --worker 10.0.0.1:7878 --cluster-key-file cluster.key
+    #[arg(
+        long,
+        value_name = "ADDR",
+        requires = "cluster_key_file",
+        conflicts_with_all = ["prefixes", "prefix_file", "dictionary", "suffixes", "regexes", "top", "quotas", "resume", "seed"]
+    )]
+    pub worker: Option<String>,
+
+    /// Fichero con la clave compartida entre coordinador y workers (16+ bytes)
+    #[arg(long, value_name = "FILE")]
+    pub cluster_key_file: Option<PathBuf>,
+
+    /// Nombre del worker ante el coordinador (por defecto, host y PID)
+    #[arg(long, requires = "worker")]
+    pub worker_name: Option<String>,
+
+    /// Buscar para un trabajo de clave dividida (creado con `generate`)
+    ///
+    /// Los prefijos y el número de resultados los fija el trabajo. Solo se
+    /// obtienen desplazamientos, que se añaden a split-offsets.jsonl en el
+    /// directorio de salida: sin el secreto del solicitante no sirven para
+    /// controlar la dirección.
+    ///
+    /// Ejemplo: --split-job split-job.json --output-dir ./desplazamientos
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["prefixes", "prefix_file", "leet", "dictionary", "suffixes", "regexes", "max_results", "top", "quotas", "seed", "coordinator", "worker"]
+    )]
+    pub split_job: Option<PathBuf>,
+}
+
+/// Subcomandos de la generación con clave dividida
+///
+/// El solicitante crea su par de claves con `generate` y comparte solo el
+/// trabajo; otra máquina lo busca con `--split-job` y devuelve los
+/// desplazamientos, que el solicitante combina con su secreto con `combine`.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Crear un par de claves y el trabajo (sin secretos) para otra máquina
+    Generate {
+        /// Prefijo(s) a buscar
+        #[arg(short, long = "prefix", required = true)]
+        prefixes: Vec<String>,
+
+        /// Número de resultados que pedir al buscador
+        #[arg(short = 'n', long, default_value_t = 1)]
+        max_results: usize,
+
+        /// Fichero del trabajo a compartir
+        #[arg(long, default_value = "split-job.json")]
+        job: PathBuf,
+
+        /// Fichero del secreto (no se sobrescribe; NO compartir)
+        #[arg(long, default_value = "split-secret.json")]
+        secret: PathBuf,
+    },
+
+    /// Combinar el secreto con los desplazamientos y guardar las claves de Tor
+    Combine {
+        /// Fichero del trabajo creado con `generate`; solo se aceptan
+        /// direcciones con alguno de sus prefijos
+        #[arg(long, default_value = "split-job.json")]
+        job: PathBuf,
+
+        /// Fichero del secreto creado con `generate`
+        #[arg(long, default_value = "split-secret.json")]
+        secret: PathBuf,
+
+        /// Fichero de desplazamientos devuelto por el buscador
+        #[arg(long, default_value = OFFSETS_FILE)]
+        offsets: PathBuf,
+
+        /// Directorio de salida para guardar las claves combinadas
+        #[arg(short, long, default_value = "./output")]
+        output_dir: PathBuf,
+    },
+
+    /// Medir la tasa de claves de esta máquina y guardar la calibración
+    ///
+    /// Mide con 1, 2, 4... hilos hasta --max-threads. Las estimaciones de
+    /// tiempo (--estimate y el modo interactivo) la usan automáticamente.
+    Bench {
+        /// Número máximo de hilos a medir
+        #[arg(short = 't', long, default_value_t = num_cpus::get())]
+        max_threads: usize,
+
+        /// Segundos de medición por número de hilos
+        #[arg(short, long, default_value_t = 3)]
+        seconds: u64,
+
+        /// Fichero de calibración (por defecto, el del usuario)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Criterios de puntuación disponibles para `--top`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScorerKind {
+    /// Más caracteres iniciales en común con alguno de los prefijos
+    LongestPrefix,
+    /// Tramo inicial más largo alternando consonantes y vocales
+    Pronounceable,
+    /// Menos dígitos en toda la dirección
+    FewestDigits,
 }
 
 impl Cli {
     /// Convierte los argumentos CLI en una configuración
-    pub fn into_config(self) -> Config {
-        Config {
-            prefixes: self.prefixes,
+    ///
+    /// Decodifica los prefijos a máscaras de bits para la búsqueda, descarta
+    /// los sufijos imposibles (ya advertidos en `validate`) y, con `--resume`,
+    /// carga el checkpoint del directorio de salida.
+    pub fn into_config(self) -> Result<Config, String> {
+        let resume = if self.resume {
+            Some(Checkpoint::load(&self.output_dir).map_err(|e| e.to_string())?)
+        } else {
+            None
+        };
+        // Sin checkpoints en dry-run (no se escribe nada) ni en modo puntuación
+        let checkpoint_interval = (!self.dry_run && self.top.is_none())
+            .then_some(self.checkpoint_interval)
+            .filter(|&seconds| seconds > 0)
+            .map(Duration::from_secs);
+
+        let report = self.report_options();
+
+        // Un trabajo de clave dividida fija los prefijos y el número de resultados
+        let split_job = match &self.split_job {
+            Some(path) => Some(SplitJob::load(path).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        let max_results = split_job
+            .as_ref()
+            .map_or(self.max_results, |job| job.max_results);
+
+        let prefixes = match &split_job {
+            Some(job) => job.prefixes.clone(),
+            None => self.all_prefixes()?,
+        };
+        let quotas = self.expanded_quotas();
+        let matcher = self.matcher(&prefixes)?;
+
+        Ok(Config {
+            prefixes,
+            matcher,
             threads: self.threads,
-            max_results: self.max_results,
+            max_results,
+            quotas,
             max_attempts: self.max_attempts,
+            deadline: self.until.map(deadline_at).transpose()?,
+            max_time: self.max_time,
+            checkpoint_interval,
+            resume,
+            key_source: match (&split_job, self.seed) {
+                (Some(job), _) => Arc::new(job.key_source().map_err(|e| e.to_string())?),
+                (None, Some(seed)) => Arc::new(SeededKeySource::new(seed)),
+                (None, None) => Arc::new(OsKeySource),
+            },
+            allow_deterministic_keys: self.insecure_allow_seeded_keys,
+            report,
+            cancel: CancelToken::new(),
+            observer: None,
             output_dir: self.output_dir,
             dry_run: self.dry_run,
+        })
+    }
+
+    /// Informe de progreso según `--stats-interval`, `--compact-stats` y `--quiet`
+    pub fn report_options(&self) -> Option<ReportOptions> {
+        if self.quiet || self.stats_interval == 0 {
+            return None;
+        }
+        Some(ReportOptions {
+            interval: Duration::from_secs(self.stats_interval),
+            style: match self.compact_stats {
+                true => ReportStyle::Compact,
+                false => ReportStyle::Full,
+            },
+        })
+    }
+
+    /// Búsqueda que el coordinador reparte a los workers
+    pub fn job(&self) -> Result<Job, String> {
+        Ok(Job {
+            prefixes: self.all_prefixes()?,
+            suffixes: self
+                .suffixes
+                .iter()
+                .filter(|suffix| explain_impossible_suffix(suffix).is_none())
+                .cloned()
+                .collect(),
+            regexes: self.regexes.clone(),
+            max_results: self.max_results,
+            max_attempts: self.max_attempts,
+            deadline: match (self.max_time, self.until) {
+                (Some(max_time), _) => Some(Instant::now() + max_time),
+                (None, until) => until.map(deadline_at).transpose()?,
+            },
+        })
+    }
+
+    /// Opciones del modo `--worker`
+    pub fn worker_options(&self) -> Result<WorkerOptions, String> {
+        let coordinator = self
+            .worker
+            .clone()
+            .ok_or_else(|| "Falta la dirección del coordinador (--worker)".to_string())?;
+
+        Ok(WorkerOptions {
+            coordinator,
+            key: self.cluster_key()?,
+            name: self.worker_name.clone().unwrap_or_else(|| {
+                let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string());
+                format!("{}-{}", host, std::process::id())
+            }),
+            threads: self.threads,
+        })
+    }
+
+    /// Clave compartida de `--cluster-key-file`
+    pub fn cluster_key(&self) -> Result<Vec<u8>, String> {
+        let path = self
+            .cluster_key_file
+            .as_ref()
+            .ok_or_else(|| "Falta la clave compartida (--cluster-key-file)".to_string())?;
+        load_cluster_key(path).map_err(|e| e.to_string())
+    }
+
+    /// Cupos de la búsqueda: los de `--quotas`, uno por variante leet
+    ///
+    /// Los prefijos sin cupo no aparecen: comparten `--max-results`.
+    fn expanded_quotas(&self) -> Vec<Quota> {
+        let mut quotas: Vec<Quota> = Vec::new();
+        for quota in &self.quotas {
+            for label in self.prefix_variants(&quota.label) {
+                if !quotas.iter().any(|q| q.label == label) {
+                    quotas.push(Quota {
+                        label,
+                        target: quota.target,
+                    });
+                }
+            }
+        }
+        quotas
+    }
+
+    /// Matcher que combina todos los patrones de la línea de comandos
+    fn matcher(&self, prefixes: &[String]) -> Result<Arc<dyn Matcher>, String> {
+        let mut matchers: Vec<Arc<dyn Matcher>> = Vec::new();
+
+        if self.top.is_some() {
+            // En modo puntuación los prefijos son objetivos del scorer
+            matchers.push(Arc::new(ScoreMatcher::new(self.scorer()?)));
+        } else if !prefixes.is_empty() {
+            matchers.push(Arc::new(PrefixMatcher::new(prefixes)?));
+        }
+
+        if let Some(path) = &self.dictionary {
+            let words = load_dictionary(path, self.min_word_length)?;
+            matchers.push(Arc::new(DictionaryMatcher::new(
+                &words,
+                self.min_word_length,
+            )?));
+        }
+
+        let suffixes: Vec<String> = self
+            .suffixes
+            .iter()
+            .filter(|suffix| explain_impossible_suffix(suffix).is_none())
+            .cloned()
+            .collect();
+        if !suffixes.is_empty() {
+            matchers.push(Arc::new(SuffixMatcher::new(&suffixes)?));
+        }
+
+        for pattern in &self.regexes {
+            matchers.push(Arc::new(RegexMatcher::new(pattern)?));
+        }
+
+        match matchers.len() {
+            0 => Err("No queda ningún patrón de búsqueda posible".to_string()),
+            1 => Ok(matchers.remove(0)),
+            _ => Ok(Arc::new(AnyMatcher::new(matchers))),
         }
     }
 
+    /// Prefijos a buscar: los de `--prefix` (con sus variantes leet), los de
+    /// `--prefix-file` y los de `--quotas`, sin duplicados
+    fn all_prefixes(&self) -> Result<Vec<String>, String> {
+        let mut prefixes = Vec::new();
+        for prefix in &self.prefixes {
+            for variant in self.prefix_variants(prefix) {
+                if !prefixes.contains(&variant) {
+                    prefixes.push(variant);
+                }
+            }
+        }
+        if let Some(path) = &self.prefix_file {
+            for prefix in read_prefix_file(path)? {
+                if !prefixes.contains(&prefix) {
+                    prefixes.push(prefix);
+                }
+            }
+        }
+        for quota in &self.quotas {
+            for variant in self.prefix_variants(&quota.label) {
+                if !prefixes.contains(&variant) {
+                    prefixes.push(variant);
+                }
+            }
+        }
+        Ok(prefixes)
+    }
+
+    /// Criterio de puntuación del modo `--top`
+    pub fn scorer(&self) -> Result<Arc<dyn Scorer>, String> {
+        Ok(match self.scorer {
+            ScorerKind::LongestPrefix => Arc::new(
+                LongestPrefixScorer::new(&self.all_prefixes()?).map_err(|e| e.to_string())?,
+            ),
+            ScorerKind::Pronounceable => Arc::new(PronounceableScorer),
+            ScorerKind::FewestDigits => Arc::new(FewestDigitsScorer),
+        })
+    }
+
+    /// Indica si hay algún patrón de búsqueda además de los sufijos
+    fn has_non_suffix_patterns(&self) -> bool {
+        !self.prefixes.is_empty()
+            || self.prefix_file.is_some()
+            || self.dictionary.is_some()
+            || !self.regexes.is_empty()
+            || self.top.is_some()
+            || !self.quotas.is_empty()
+    }
+
     /// Valida los argumentos de la CLI
     pub fn validate(&self) -> Result<(), String> {
-        // Validar que hay al menos un prefijo
-        if self.prefixes.is_empty() {
-            return Err("Debe especificar al menos un prefijo con --prefix".to_string());
+        // Un worker no tiene patrones propios: los recibe del coordinador
+        if self.worker.is_some() {
+            return Ok(());
+        }
+
+        // Los patrones de un trabajo de clave dividida vienen en el fichero
+        if let Some(path) = &self.split_job {
+            let job = SplitJob::load(path).map_err(|e| e.to_string())?;
+            PrefixMatcher::new(&job.prefixes)?;
+            job.key_source().map_err(|e| e.to_string())?;
+            if job.max_results == 0 {
+                return Err("El trabajo pide 0 resultados".to_string());
+            }
+            if self.threads == 0 {
+                return Err("El número de hilos debe ser mayor que 0".to_string());
+            }
+            return Ok(());
+        }
+
+        // Validar que hay al menos un patrón
+        if self.suffixes.is_empty() && !self.has_non_suffix_patterns() {
+            return Err(
+                "Debe especificar al menos un prefijo (--prefix, --prefix-file o --dictionary), sufijo (--suffix) o expresión regular (--regex)"
+                    .to_string(),
+            );
+        }
+
+        // Las claves de una semilla conocida no deben acabar en un servicio real
+        if self.seed.is_some() && !self.dry_run && !self.insecure_allow_seeded_keys {
+            return Err(
+                "--seed genera claves reproducibles e inseguras: use --dry-run o, solo para pruebas, --insecure-allow-seeded-keys"
+                    .to_string(),
+            );
+        }
+
+        if self.dictionary.is_some() && self.min_word_length == 0 {
+            return Err("La longitud mínima de palabra debe ser mayor que 0".to_string());
+        }
+
+        // Las expresiones regulares imposibles se rechazan antes de buscar
+        for pattern in &self.regexes {
+            RegexMatcher::new(pattern)?;
         }
 
         // Validar que los prefijos no estén vacíos
@@ -78,10 +613,18 @@ impl Cli {
 
             // Validar caracteres del prefijo
             for ch in prefix.chars() {
+                if self.leet && leet_lookalikes(ch).is_some() {
+                    continue;
+                }
                 if !ch.is_ascii_lowercase() && !('2'..='7').contains(&ch) {
+                    let hint = if leet_lookalikes(ch).is_some() {
+                        " (use --leet para sustituir 0, 1, 8 y 9 por letras parecidas)"
+                    } else {
+                        ""
+                    };
                     return Err(format!(
-                        "Prefijo '{}' contiene caracteres inválidos. Solo se permiten a-z y 2-7",
-                        prefix
+                        "Prefijo '{}' contiene caracteres inválidos. Solo se permiten a-z y 2-7{}",
+                        prefix, hint
                     ));
                 }
             }
@@ -104,6 +647,77 @@ impl Cli {
             }
         }
 
+        // Advertir sobre sufijos imposibles por el formato v3
+        let mut possible_suffixes = 0;
+        for suffix in &self.suffixes {
+            match explain_impossible_suffix(suffix) {
+                Some(reason) => {
+                    eprintln!(
+                        "⚠️  ADVERTENCIA: El sufijo '{}' nunca puede aparecer: {}.",
+                        suffix, reason
+                    );
+                    eprintln!("    Se descartará de la búsqueda.");
+                }
+                None => possible_suffixes += 1,
+            }
+        }
+        if possible_suffixes == 0 && !self.has_non_suffix_patterns() {
+            return Err(
+                "Ninguno de los sufijos indicados es posible en una dirección v3".to_string(),
+            );
+        }
+
+        // Los cupos se refieren a prefijos (a sus variantes, con --leet)
+        for quota in &self.quotas {
+            let variants = self.prefix_variants(&quota.label);
+            if variants
+                .iter()
+                .any(|variant| variant.chars().any(|ch| base32_value(ch).is_none()))
+            {
+                let leet_chars = quota.label.chars().any(|ch| leet_lookalikes(ch).is_some());
+                let hint = if leet_chars && !self.leet {
+                    " (use --leet para sustituir 0, 1, 8 y 9 por letras parecidas)"
+                } else {
+                    ""
+                };
+                return Err(format!(
+                    "Cupo '{}:{}' inválido: no corresponde a ningún prefijo de a-z y 2-7{}",
+                    quota.label, quota.target, hint
+                ));
+            }
+        }
+
+        // Advertir de prefijos que otro más corto ya cubre (en modo puntuación
+        // los prefijos son objetivos del scorer, no condiciones de acierto)
+        if self.top.is_none() {
+            if let Ok(prefixes) = self.all_prefixes() {
+                let set = PrefixSet::new(&prefixes);
+                let covered: Vec<_> = set.covered().collect();
+                for prefix in covered.iter().take(LISTED_COVERED_PREFIXES) {
+                    let shorter = prefix.covered_by.as_deref().unwrap_or_default();
+                    eprintln!(
+                        "⚠️  ADVERTENCIA: El prefijo '{}' ya está cubierto por '{}'.",
+                        prefix.prefix, shorter
+                    );
+                    eprintln!(
+                        "    Toda dirección que empieza por '{}' empieza por '{}': no acelera la búsqueda, solo cambia la etiqueta (y el cupo) de esos aciertos.",
+                        prefix.prefix, shorter
+                    );
+                }
+                if covered.len() > LISTED_COVERED_PREFIXES {
+                    eprintln!(
+                        "    ... y {} prefijos cubiertos más.",
+                        covered.len() - LISTED_COVERED_PREFIXES
+                    );
+                }
+            }
+        }
+
+        // Una hora límite ya pasada terminaría la búsqueda nada más empezar
+        if let Some(until) = self.until {
+            deadline_at(until)?;
+        }
+
         // Validar número de hilos
         if self.threads == 0 {
             return Err("El número de hilos debe ser mayor que 0".to_string());
@@ -114,9 +728,40 @@ impl Cli {
             return Err("El número máximo de resultados debe ser mayor que 0".to_string());
         }
 
+        // El modo puntuación necesita un top y un presupuesto
+        if let Some(top) = self.top {
+            if top == 0 {
+                return Err("El top debe tener al menos 1 dirección".to_string());
+            }
+            if self.max_attempts.is_none() && self.max_time.is_none() && self.until.is_none() {
+                return Err(
+                    "El modo --top necesita un presupuesto: --max-attempts, --max-time o --until"
+                        .to_string(),
+                );
+            }
+            if self.scorer == ScorerKind::LongestPrefix
+                && self.prefixes.is_empty()
+                && self.prefix_file.is_none()
+            {
+                return Err(
+                    "El criterio longest-prefix necesita --prefix o --prefix-file (o use otro --scorer)"
+                        .to_string(),
+                );
+            }
+        }
+
         Ok(())
     }
 
+    /// Variantes base32 de un prefijo (solo más de una con `--leet`)
+    fn prefix_variants(&self, prefix: &str) -> Vec<String> {
+        if self.leet {
+            expand_leet(prefix)
+        } else {
+            vec![prefix.to_string()]
+        }
+    }
+
     /// Muestra información de configuración antes de iniciar
     pub fn print_config(&self) {
         println!("╔════════════════════════════════════════════════════════════════╗");
@@ -124,15 +769,70 @@ impl Cli {
         println!("╚════════════════════════════════════════════════════════════════╝");
         println!();
         println!("📋 Configuración:");
-        println!("   • Prefijos buscados: {}", self.prefixes.join(", "));
+        if !self.prefixes.is_empty() {
+            println!("   • Prefijos buscados: {}", self.prefixes.join(", "));
+        }
+        if self.leet {
+            for prefix in &self.prefixes {
+                let variants = self.prefix_variants(prefix);
+                if variants.len() > 1 || variants[0] != *prefix {
+                    println!("   • Variantes de '{}': {}", prefix, variants.join(", "));
+                }
+            }
+        }
+        if let Some(path) = &self.prefix_file {
+            println!("   • Fichero de prefijos: {}", path.display());
+        }
+        if let Some(path) = &self.dictionary {
+            println!(
+                "   • Diccionario: {} (palabras de {}+ caracteres)",
+                path.display(),
+                self.min_word_length
+            );
+        }
+        if !self.suffixes.is_empty() {
+            println!("   • Sufijos buscados: {}", self.suffixes.join(", "));
+        }
+        for pattern in &self.regexes {
+            println!("   • Expresión regular: /{}/", pattern);
+        }
+        if let Some(addr) = &self.coordinator {
+            println!("   • Coordinador en: {}", addr);
+        }
+        if let Some(path) = &self.split_job {
+            println!("   • Trabajo de clave dividida: {}", path.display());
+        }
         println!("   • Hilos: {}", self.threads);
-        println!("   • Resultados máximos: {}", self.max_results);
+        if let Some(seed) = self.seed {
+            println!("   • Fuente de claves: ChaCha20 con semilla {} (⚠️  INSEGURA)", seed);
+        }
+        match self.top {
+            Some(top) => println!("   • Modo puntuación: top {} ({:?})", top, self.scorer),
+            None if !self.quotas.is_empty() => {
+                let quotas: Vec<String> = self
+                    .quotas
+                    .iter()
+                    .map(|quota| format!("{}:{}", quota.label, quota.target))
+                    .collect();
+                println!("   • Cupos: {}", quotas.join(", "));
+            }
+            None if self.split_job.is_some() => {
+                println!("   • Resultados máximos: los que pida el trabajo")
+            }
+            None => println!("   • Resultados máximos: {}", self.max_results),
+        }
         
         if let Some(max_attempts) = self.max_attempts {
             println!("   • Intentos máximos: {}", max_attempts);
         } else {
             println!("   • Intentos máximos: ilimitado");
         }
+        if let Some(max_time) = self.max_time {
+            println!("   • Tiempo máximo: {}", format_duration(max_time.as_secs_f64()));
+        }
+        if let Some(until) = self.until {
+            println!("   • Detener a las: {}", until.with_timezone(&chrono::Local).to_rfc3339());
+        }
         
         if self.dry_run {
             println!("   • Modo: DRY-RUN (no se guardarán claves)");
@@ -144,28 +844,294 @@ impl Cli {
     }
 
     /// Muestra estimaciones de tiempo para los prefijos
-    pub fn print_estimates(&self, rate: f64) {
-        use crate::search::{estimate_time, format_duration};
+    ///
+    /// Usa la tasa calibrada con `bench` para los hilos configurados o, si
+    /// la máquina no está calibrada, `ASSUMED_RATE`.
+    pub fn print_estimates(&self) {
+        use vanity_onion_v3::bench::{machine_rate, ASSUMED_RATE};
+        use vanity_onion_v3::generator::suffix_probability;
+        use vanity_onion_v3::search::{estimate_time_for_probability, format_eta_percentiles};
 
-        println!("⏱️  Estimaciones de tiempo (basadas en tasa actual: {:.0} intentos/s):", rate);
+        let rate = match machine_rate(self.threads) {
+            Some(rate) => {
+                println!(
+                    "⏱️  Estimaciones de tiempo (calibradas: {:.0} intentos/s con {} hilos):",
+                    rate, self.threads
+                );
+                rate
+            }
+            None => {
+                println!(
+                    "⏱️  Estimaciones de tiempo (supuestos {:.0} intentos/s; mida esta máquina con `torge-forge-cli bench`):",
+                    ASSUMED_RATE
+                );
+                ASSUMED_RATE
+            }
+        };
+        println!("   (tiempo medio y, entre paréntesis, hasta el 50%, 90% y 99% de probabilidad)");
         println!();
-        
+        let print_percentiles = |probability: f64| {
+            println!("       ({})", format_eta_percentiles(probability, rate));
+        };
+        let set = PrefixSet::new(&self.all_prefixes().unwrap_or_default());
+
         for prefix in &self.prefixes {
-            let time = estimate_time(prefix, rate);
-            let formatted = format_duration(time);
-            let probability = 1.0 / 32f64.powi(prefix.len() as i32);
-            
+            // Las variantes leet tienen la misma longitud y son disjuntas
+            let variants = self.prefix_variants(prefix).len();
+            let probability = variants as f64 / 32f64.powi(prefix.len() as i32);
+            let formatted = format_duration(estimate_time_for_probability(probability, rate));
+
+            let detail = if variants > 1 {
+                format!(", {} variantes", variants)
+            } else {
+                String::new()
+            };
             println!(
-                "   • '{}' ({} caracteres): ~{} (probabilidad: 1 en {:.0})",
+                "   • '{}' ({} caracteres{}): ~{} (probabilidad: 1 en {:.0})",
                 prefix,
                 prefix.len(),
+                detail,
                 formatted,
                 1.0 / probability
             );
+            let covered_by = self.prefix_variants(prefix).iter().find_map(|variant| {
+                set.prefixes
+                    .iter()
+                    .find(|p| p.prefix == *variant)?
+                    .covered_by
+                    .clone()
+            });
+            match covered_by {
+                Some(shorter) => println!(
+                    "       ⚠️  cubierto por '{}': no cuenta en la probabilidad del conjunto",
+                    shorter
+                ),
+                None => print_percentiles(probability),
+            }
         }
-        
+
+        if let Some(path) = &self.prefix_file {
+            if let Ok(matcher) = read_prefix_file(path).and_then(|p| PrefixMatcher::new(&p)) {
+                let probability = matcher.probability();
+                println!(
+                    "   • fichero {} ({} prefijos): ~{} (probabilidad: 1 en {:.0})",
+                    path.display(),
+                    matcher.prefixes().count(),
+                    format_duration(estimate_time_for_probability(probability, rate)),
+                    1.0 / probability
+                );
+                print_percentiles(probability);
+            }
+        }
+
+        if let Some(path) = &self.dictionary {
+            let matcher = load_dictionary(path, self.min_word_length)
+                .and_then(|words| DictionaryMatcher::new(&words, self.min_word_length));
+            if let Ok(matcher) = matcher {
+                let probability = matcher.probability();
+                println!(
+                    "   • {}: ~{} (probabilidad: 1 en {:.0})",
+                    matcher.name(),
+                    format_duration(estimate_time_for_probability(probability, rate)),
+                    1.0 / probability
+                );
+                print_percentiles(probability);
+            }
+        }
+
+        for suffix in &self.suffixes {
+            let probability = suffix_probability(suffix);
+            if probability == 0.0 {
+                println!("   • sufijo '{}': imposible en una dirección v3", suffix);
+                continue;
+            }
+
+            let time = estimate_time_for_probability(probability, rate);
+            println!(
+                "   • sufijo '{}' ({} caracteres): ~{} (probabilidad: 1 en {:.0})",
+                suffix,
+                suffix.len(),
+                format_duration(time),
+                1.0 / probability
+            );
+            print_percentiles(probability);
+        }
+
+        for pattern in &self.regexes {
+            let Ok(matcher) = RegexMatcher::new(pattern) else {
+                continue;
+            };
+            let probability = matcher.probability();
+            let time = estimate_time_for_probability(probability, rate);
+            println!(
+                "   • regex /{}/: ~{} (probabilidad: 1 en {:.0})",
+                pattern,
+                format_duration(time),
+                1.0 / probability
+            );
+            print_percentiles(probability);
+        }
+
+        if self.top.is_none() {
+            self.print_goal_estimate(rate);
+        }
+        println!();
+    }
+
+    /// Estimación del conjunto de patrones y de la condición de parada
+    ///
+    /// La búsqueda acepta un acierto de cualquier patrón, así que la
+    /// probabilidad por intento es la de la unión; el objetivo completo son
+    /// `--max-results` aciertos o, con `--quotas`, todos los cupos.
+    fn print_goal_estimate(&self, rate: f64) {
+        use vanity_onion_v3::search::{estimate_time_for_probability, ETA_CONFIDENCES};
+
+        let Ok(prefixes) = self.all_prefixes() else {
+            return;
+        };
+        let Ok(matcher) = self.matcher(&prefixes) else {
+            return;
+        };
+        let set = PrefixSet::new(&prefixes);
+        let goal = if self.quotas.is_empty() {
+            Some(Goal::results(matcher.name(), matcher.probability(), self.max_results))
+        } else {
+            Goal::quotas(&set, &self.expanded_quotas())
+        };
+        let Some(goal) = goal else {
+            return;
+        };
+        let time = |attempts: f64| format_duration(attempts / rate);
+
         println!();
+        let probability = matcher.probability();
+        println!(
+            "   📦 Cualquier patrón: ~{} por acierto (probabilidad: 1 en {:.0})",
+            format_duration(estimate_time_for_probability(probability, rate)),
+            1.0 / probability
+        );
+        let covered = set.covered().count();
+        if covered > 0 {
+            println!(
+                "       ⚠️  {} prefijo(s) cubiertos por otro más corto no cuentan en la unión",
+                covered
+            );
+        }
+
+        if goal.terms.len() > 1 {
+            for term in &goal.terms {
+                println!(
+                    "   • Cupo '{}' ({}): ~{} (probabilidad por intento: 1 en {:.0})",
+                    term.label,
+                    term.target,
+                    time(term.expected_attempts()),
+                    1.0 / term.probability
+                );
+            }
+        }
+
+        let description = if self.quotas.is_empty() {
+            format!("{} resultado(s)", self.max_results)
+        } else {
+            "todos los cupos".to_string()
+        };
+        let percentiles: Vec<String> = ETA_CONFIDENCES
+            .iter()
+            .map(|&confidence| {
+                format!(
+                    "{:.0}%: ~{}",
+                    confidence * 100.0,
+                    time(goal.attempts_for_confidence(confidence))
+                )
+            })
+            .collect();
+        println!(
+            "   🎯 Objetivo completo ({}): ~{}",
+            description,
+            time(goal.expected_attempts())
+        );
+        println!("       ({})", percentiles.join(" | "));
+
+        // Límites de la búsqueda traducidos a intentos
+        let seconds = [
+            self.max_time.map(|limit| limit.as_secs_f64()),
+            self.until
+                .map(|until| (until - Utc::now()).num_milliseconds().max(0) as f64 / 1000.0),
+        ];
+        let limit = seconds
+            .into_iter()
+            .flatten()
+            .map(|seconds| seconds * rate)
+            .chain(self.max_attempts.map(|attempts| attempts as f64))
+            .reduce(f64::min);
+        if let Some(attempts) = limit {
+            println!(
+                "   ⏳ Con los límites configurados (~{:.0} intentos): {:.1}% de probabilidad de completarlo",
+                attempts,
+                goal.chance_by(attempts) * 100.0
+            );
+        }
+    }
+}
+
+/// Interpreta el argumento de `--until` (RFC 3339)
+fn parse_until(text: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|until| until.with_timezone(&Utc))
+        .map_err(|e| {
+            format!(
+                "Fecha inválida '{}' ({}): use RFC 3339, p. ej. 2025-06-01T06:00:00+02:00",
+                text, e
+            )
+        })
+}
+
+/// Lee un fichero de prefijos (uno por línea)
+///
+/// Las líneas se normalizan a minúsculas; se ignoran las vacías, las que
+/// empiezan por '#' y los duplicados.
+///
+/// # Arguments
+///
+/// * `path` - Ruta del fichero
+///
+/// # Returns
+///
+/// Result con los prefijos leídos, o un error con la línea inválida
+pub fn read_prefix_file(path: &Path) -> Result<Vec<String>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("No se pudo leer '{}': {}", path.display(), e))?;
+
+    let mut prefixes = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let prefix = line.trim().to_lowercase();
+        if prefix.is_empty() || prefix.starts_with('#') {
+            continue;
+        }
+
+        if let Some(ch) = prefix.chars().find(|&ch| base32_value(ch).is_none()) {
+            return Err(format!(
+                "{}:{}: el prefijo '{}' contiene el carácter inválido '{}'. Solo se permiten a-z y 2-7",
+                path.display(),
+                number + 1,
+                prefix,
+                ch
+            ));
+        }
+
+        if !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
     }
+
+    if prefixes.is_empty() {
+        return Err(format!(
+            "El fichero '{}' no contiene prefijos",
+            path.display()
+        ));
+    }
+
+    Ok(prefixes)
 }
 
 // Función auxiliar para obtener el número de CPUs
@@ -180,72 +1146,381 @@ mod num_cpus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use vanity_onion_v3::generator::BASE32_ALPHABET;
+
+    /// Parsea los argumentos como la línea de órdenes (sin el nombre del programa)
+    fn cli(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("torge-forge-cli").chain(args.iter().copied())).unwrap()
+    }
 
     #[test]
     fn test_validate_valid_prefix() {
-        let cli = Cli {
-            prefixes: vec!["test".to_string(), "abc2".to_string()],
-            threads: 4,
-            max_results: 1,
-            max_attempts: None,
-            output_dir: PathBuf::from("./output"),
-            dry_run: false,
-            stats_interval: 10,
-            quiet: false,
-            estimate: false,
-        };
+        let cli = cli(&["-p", "test", "-p", "abc2", "-t", "4"]);
 
         assert!(cli.validate().is_ok());
     }
 
     #[test]
     fn test_validate_invalid_prefix() {
-        let cli = Cli {
-            prefixes: vec!["TEST".to_string()], // Mayúsculas no permitidas
-            threads: 4,
-            max_results: 1,
-            max_attempts: None,
-            output_dir: PathBuf::from("./output"),
-            dry_run: false,
-            stats_interval: 10,
-            quiet: false,
-            estimate: false,
-        };
+        let cli = cli(&["-p", "TEST", "-t", "4"]); // Mayúsculas no permitidas
 
         assert!(cli.validate().is_err());
     }
 
     #[test]
     fn test_validate_empty_prefix() {
-        let cli = Cli {
-            prefixes: vec!["".to_string()],
-            threads: 4,
-            max_results: 1,
-            max_attempts: None,
-            output_dir: PathBuf::from("./output"),
-            dry_run: false,
-            stats_interval: 10,
-            quiet: false,
-            estimate: false,
-        };
+        let cli = cli(&["-p", ""]);
 
         assert!(cli.validate().is_err());
     }
 
     #[test]
     fn test_validate_zero_threads() {
-        let cli = Cli {
-            prefixes: vec!["test".to_string()],
-            threads: 0,
-            max_results: 1,
-            max_attempts: None,
-            output_dir: PathBuf::from("./output"),
-            dry_run: false,
-            stats_interval: 10,
-            quiet: false,
-            estimate: false,
-        };
+        let cli = cli(&["-p", "test", "-t", "0"]);
+
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_suffixes() {
+        let mut cli = cli(&["--suffix", "shop", "--suffix", "xyd"]);
+
+        // "shop" es imposible pero "xyd" no: se advierte y se descarta
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.matcher.name(), "sufijo xyd");
 
+        cli = Cli::parse_from(["torge-forge-cli", "--suffix", "shop"]);
         assert!(cli.validate().is_err());
     }
+
+    #[test]
+    fn test_validate_regex() {
+        let cli = Cli::parse_from(["torge-forge-cli", "--regex", "^(shop|store)[a-z]{2}"]);
+        assert!(cli.validate().is_ok());
+        assert_eq!(
+            cli.into_config().unwrap().matcher.name(),
+            "regex /^(shop|store)[a-z]{2}/"
+        );
+
+        let cli = Cli::parse_from(["torge-forge-cli", "--regex", "shop$"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_prefix_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prefijos.txt");
+        let mut content = String::from("# marcas\nShop\n\nshop\n");
+        for i in 0..40 {
+            content.push_str(&format!("b{}x\n", BASE32_ALPHABET[i % 32] as char));
+        }
+        std::fs::write(&path, content).unwrap();
+
+        let prefixes = read_prefix_file(&path).unwrap();
+        assert_eq!(prefixes.len(), 33);
+        assert_eq!(prefixes[0], "shop");
+
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "--prefix",
+            "blog",
+            "--prefix-file",
+            path.to_str().unwrap(),
+        ]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.prefixes.len(), 34);
+        assert!(config.matcher.name().starts_with("34 prefijos (blog, shop"));
+
+        std::fs::write(&path, "shop\nca$h\n").unwrap();
+        let err = read_prefix_file(&path).unwrap_err();
+        assert!(err.contains(":2:"), "{}", err);
+    }
+
+    #[test]
+    fn test_dictionary_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("palabras.txt");
+        std::fs::write(&path, "camión\nsol\nnoche\nhab1tación\n").unwrap();
+
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "--dictionary",
+            path.to_str().unwrap(),
+            "--min-word-length",
+            "4",
+        ]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(
+            config.matcher.name(),
+            "diccionario (2 palabras de 4+ caracteres)"
+        );
+        assert_eq!(
+            config.matcher.dictionary_word("camion"),
+            Some("camión".to_string())
+        );
+
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "--dictionary",
+            path.to_str().unwrap(),
+            "--min-word-length",
+            "10",
+        ]);
+        assert!(cli.into_config().is_err());
+    }
+
+    #[test]
+    fn test_validate_leet() {
+        let cli = Cli::parse_from(["torge-forge-cli", "--prefix", "b10g"]);
+        let err = cli.validate().unwrap_err();
+        assert!(err.contains("--leet"), "{}", err);
+
+        let cli = Cli::parse_from(["torge-forge-cli", "--prefix", "b10g", "--leet"]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.prefixes, vec!["blog", "biog"]);
+        assert!((config.matcher.probability() - 2.0 / 32f64.powi(4)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_validate_top() {
+        let cli = Cli::parse_from(["torge-forge-cli", "--top", "3", "--scorer", "pronounceable"]);
+        assert!(cli.validate().is_err()); // Sin presupuesto
+
+        let cli = Cli::parse_from(["torge-forge-cli", "--top", "3", "--max-attempts", "1000"]);
+        assert!(cli.validate().is_err()); // longest-prefix sin prefijos
+
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "--prefix",
+            "torge",
+            "--top",
+            "3",
+            "--max-time",
+            "1m",
+        ]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.matcher.name(), "prefijo más largo");
+        assert_eq!(config.max_time, Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_validate_seed() {
+        let cli = Cli::parse_from(["torge-forge-cli", "--prefix", "abc", "--seed", "42"]);
+        assert!(cli.validate().is_err()); // Guardaría claves inseguras
+
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--seed", "42", "--dry-run"]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert!(config.key_source.is_deterministic());
+        assert!(!config.allow_deterministic_keys);
+
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "--prefix",
+            "abc",
+            "--seed",
+            "42",
+            "--insecure-allow-seeded-keys",
+        ]);
+        assert!(cli.validate().is_ok());
+        assert!(cli.into_config().unwrap().allow_deterministic_keys);
+
+        // El override no tiene sentido sin semilla
+        let parsed = Cli::try_parse_from([
+            "torge-forge-cli",
+            "--prefix",
+            "abc",
+            "--insecure-allow-seeded-keys",
+        ]);
+        assert!(parsed.is_err());
+    }
+
+    #[test]
+    fn test_split_key_commands() {
+        let cli = Cli::parse_from(["torge-forge-cli", "generate", "-p", "torge", "-n", "2"]);
+        assert_eq!(
+            cli.command,
+            Some(Command::Generate {
+                prefixes: vec!["torge".to_string()],
+                max_results: 2,
+                job: PathBuf::from("split-job.json"),
+                secret: PathBuf::from("split-secret.json"),
+            })
+        );
+        assert!(Cli::try_parse_from(["torge-forge-cli", "generate"]).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("split-job.json");
+        let (job, _) = SplitJob::create(&["torge".to_string()], 3);
+        job.save(&path).unwrap();
+
+        let cli = Cli::parse_from(["torge-forge-cli", "--split-job", path.to_str().unwrap()]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.prefixes, vec!["torge"]);
+        assert_eq!(config.max_results, 3);
+        assert!(config.key_source.name().starts_with("clave dividida"));
+
+        // Los patrones los fija el trabajo
+        let parsed = Cli::try_parse_from([
+            "torge-forge-cli",
+            "--split-job",
+            path.to_str().unwrap(),
+            "--prefix",
+            "abc",
+        ]);
+        assert!(parsed.is_err());
+    }
+
+    #[test]
+    fn test_bench_command() {
+        let cli = Cli::parse_from(["torge-forge-cli", "bench", "-t", "4", "--seconds", "1"]);
+        assert_eq!(
+            cli.command,
+            Some(Command::Bench {
+                max_threads: 4,
+                seconds: 1,
+                output: None,
+            })
+        );
+
+        let cli = Cli::parse_from(["torge-forge-cli", "bench", "--output", "calibracion.json"]);
+        match cli.command {
+            Some(Command::Bench { seconds, output, .. }) => {
+                assert_eq!(seconds, 3);
+                assert_eq!(output, Some(PathBuf::from("calibracion.json")));
+            }
+            other => panic!("subcomando inesperado: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_report_options() {
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--stats-interval", "5"]);
+        let report = cli.into_config().unwrap().report.unwrap();
+        assert_eq!(report.interval, Duration::from_secs(5));
+        assert_eq!(report.style, ReportStyle::Full);
+
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--compact-stats"]);
+        assert_eq!(cli.report_options().unwrap().style, ReportStyle::Compact);
+
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--quiet"]);
+        assert!(cli.report_options().is_none());
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--stats-interval", "0"]);
+        assert!(cli.report_options().is_none());
+
+        assert!(Cli::try_parse_from(["torge-forge-cli", "-p", "abc", "--compact-stats", "-q"]).is_err());
+    }
+
+    #[test]
+    fn test_time_limits() {
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--max-time", "1h30m"]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.max_time, Some(Duration::from_secs(5400)));
+        assert!(config.deadline.is_none());
+
+        let until = (chrono::Utc::now() + chrono::Duration::hours(2)).to_rfc3339();
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--until", &until]);
+        assert!(cli.validate().is_ok());
+        let deadline = cli.into_config().unwrap().deadline.unwrap();
+        assert!(deadline > Instant::now() + Duration::from_secs(7000));
+
+        // Una hora ya pasada o mal escrita se rechaza
+        let past = (chrono::Utc::now() - chrono::Duration::hours(2)).to_rfc3339();
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--until", &past]);
+        assert!(cli.validate().is_err());
+        assert!(Cli::try_parse_from(["torge-forge-cli", "-p", "abc", "--until", "mañana"]).is_err());
+        assert!(Cli::try_parse_from(["torge-forge-cli", "-p", "abc", "--max-time", "6x"]).is_err());
+
+        // El coordinador también detiene a los workers al pasar el límite
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "-p",
+            "abc",
+            "--coordinator",
+            "127.0.0.1:0",
+            "--cluster-key-file",
+            "cluster.key",
+            "--max-time",
+            "1h",
+        ]);
+        let deadline = cli.job().unwrap().deadline.unwrap();
+        assert!(deadline > Instant::now() + Duration::from_secs(3500));
+        assert!(Cli::try_parse_from([
+            "torge-forge-cli",
+            "-p",
+            "abc",
+            "--max-time",
+            "6h",
+            "--until",
+            &until
+        ])
+        .is_err());
+
+        // También sirven de presupuesto para --top
+        let cli = Cli::parse_from(["torge-forge-cli", "-p", "abc", "--top", "3", "--max-time", "10m"]);
+        assert!(cli.validate().is_ok());
+    }
+
+    #[test]
+    fn test_quotas() {
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "--prefix",
+            "shop",
+            "--prefix",
+            "news",
+            "--quotas",
+            "shop:3,blog:1",
+        ]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.prefixes, vec!["shop", "news", "blog"]);
+
+        let quotas: Vec<(&str, usize)> = config
+            .quotas
+            .iter()
+            .map(|quota| (quota.label.as_str(), quota.target))
+            .collect();
+        // "news" no tiene cupo propio: solo cuenta para --max-results
+        assert_eq!(quotas, vec![("shop", 3), ("blog", 1)]);
+        assert_eq!(config.max_results, 1);
+
+        assert!(Cli::try_parse_from(["torge-forge-cli", "--quotas", "shop"]).is_err());
+        assert!(Cli::try_parse_from(["torge-forge-cli", "--quotas", "shop:0"]).is_err());
+        let cli = Cli::parse_from(["torge-forge-cli", "--quotas", "sh0p:2"]);
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn test_quotas_leet() {
+        // El cupo se aplica a cada variante del prefijo
+        let cli = Cli::parse_from([
+            "torge-forge-cli",
+            "--prefix",
+            "b10g",
+            "--leet",
+            "--quotas",
+            "b10g:1",
+        ]);
+        assert!(cli.validate().is_ok());
+        let config = cli.into_config().unwrap();
+        assert_eq!(config.prefixes, vec!["blog", "biog"]);
+        let quotas: Vec<(&str, usize)> = config
+            .quotas
+            .iter()
+            .map(|quota| (quota.label.as_str(), quota.target))
+            .collect();
+        assert_eq!(quotas, vec![("blog", 1), ("biog", 1)]);
+
+        let cli = Cli::parse_from(["torge-forge-cli", "--prefix", "blog", "--quotas", "b10g:1"]);
+        let err = cli.validate().unwrap_err();
+        assert!(err.contains("--leet"), "{}", err);
+    }
 }
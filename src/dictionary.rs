@@ -0,0 +1,195 @@
+use crate::generator::base32_value;
+use std::path::Path;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Palabra de diccionario normalizada al alfabeto base32
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryWord {
+    /// Forma buscada en la dirección (a-z y 2-7)
+    pub normalized: String,
+    /// Palabra tal como aparece en el diccionario
+    pub original: String,
+}
+
+/// Normaliza una palabra al alfabeto base32 de las direcciones v3
+///
+/// Quita acentos y diacríticos ("camión" → "camion", "año" → "ano") y pasa a
+/// minúsculas. Las palabras que siguen teniendo caracteres fuera de a-z y 2-7
+/// (por ejemplo 0, 1, 8, 9, guiones o apóstrofos) se descartan.
+///
+/// # Arguments
+///
+/// * `word` - Palabra original
+///
+/// # Returns
+///
+/// Option con la palabra normalizada, o None si no es representable
+pub fn normalize_word(word: &str) -> Option<String> {
+    let normalized: String = word
+        .trim()
+        .nfd()
+        .filter(|&ch| !is_combining_mark(ch))
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    if normalized.is_empty() || normalized.chars().any(|ch| base32_value(ch).is_none()) {
+        return None;
+    }
+
+    Some(normalized)
+}
+
+/// Equivalentes base32 de los caracteres "leet" que no están en el alfabeto
+///
+/// 0, 1, 8 y 9 no existen en base32; se sustituyen por las letras a las que
+/// se parecen (0→o, 1→l/i, 8→b, 9→g).
+pub fn leet_lookalikes(ch: char) -> Option<&'static [char]> {
+    match ch {
+        '0' => Some(&['o']),
+        '1' => Some(&['l', 'i']),
+        '8' => Some(&['b']),
+        '9' => Some(&['g']),
+        _ => None,
+    }
+}
+
+/// Expande un prefijo con 0, 1, 8 o 9 en todas sus variantes base32
+///
+/// # Arguments
+///
+/// * `prefix` - Prefijo con caracteres leet (por ejemplo "b10g")
+///
+/// # Returns
+///
+/// Vector con todas las combinaciones de sustituciones ("blog", "biog"); un
+/// prefijo sin caracteres leet se devuelve tal cual
+pub fn expand_leet(prefix: &str) -> Vec<String> {
+    let mut variants = vec![String::with_capacity(prefix.len())];
+
+    for ch in prefix.chars() {
+        match leet_lookalikes(ch) {
+            Some(lookalikes) => {
+                variants = variants
+                    .iter()
+                    .flat_map(|variant| {
+                        lookalikes.iter().map(move |&replacement| {
+                            let mut next = variant.clone();
+                            next.push(replacement);
+                            next
+                        })
+                    })
+                    .collect();
+            }
+            None => variants.iter_mut().for_each(|variant| variant.push(ch)),
+        }
+    }
+
+    variants
+}
+
+/// Lee un diccionario (una palabra por línea) y lo normaliza
+///
+/// Se ignoran las líneas vacías, las que empiezan por '#', las palabras no
+/// representables en base32, las más cortas que `min_length` (tras normalizar)
+/// y los duplicados; de varias palabras con la misma forma normalizada se
+/// conserva la primera.
+///
+/// # Arguments
+///
+/// * `path` - Ruta del diccionario
+/// * `min_length` - Longitud mínima de las palabras aceptadas
+///
+/// # Returns
+///
+/// Result con las palabras normalizadas, o un error si no queda ninguna
+pub fn load_dictionary(path: &Path, min_length: usize) -> Result<Vec<DictionaryWord>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("No se pudo leer '{}': {}", path.display(), e))?;
+
+    let words = normalize_words(content.lines(), min_length);
+    if words.is_empty() {
+        return Err(format!(
+            "El diccionario '{}' no contiene palabras válidas de al menos {} caracteres",
+            path.display(),
+            min_length
+        ));
+    }
+
+    Ok(words)
+}
+
+/// Normaliza una lista de palabras con las reglas de `load_dictionary`
+pub fn normalize_words<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    min_length: usize,
+) -> Vec<DictionaryWord> {
+    let mut seen = std::collections::HashSet::new();
+    let mut words = Vec::new();
+
+    for line in lines {
+        let original = line.trim();
+        if original.is_empty() || original.starts_with('#') {
+            continue;
+        }
+
+        let Some(normalized) = normalize_word(original) else {
+            continue;
+        };
+        if normalized.len() < min_length || !seen.insert(normalized.clone()) {
+            continue;
+        }
+
+        words.push(DictionaryWord {
+            normalized,
+            original: original.to_string(),
+        });
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_word() {
+        assert_eq!(normalize_word("Camión"), Some("camion".to_string()));
+        assert_eq!(normalize_word("año"), Some("ano".to_string()));
+        assert_eq!(normalize_word("pingüino"), Some("pinguino".to_string()));
+        assert_eq!(normalize_word("web3"), Some("web3".to_string()));
+        assert_eq!(normalize_word("room101"), None);
+        assert_eq!(normalize_word("arco-iris"), None);
+        assert_eq!(normalize_word(""), None);
+    }
+
+    #[test]
+    fn test_normalize_words() {
+        let words = normalize_words(
+            [
+                "# comentario",
+                "casa",
+                "Casa",
+                "sol",
+                "",
+                "camión",
+                "camion",
+                "h8",
+            ],
+            4,
+        );
+        let normalized: Vec<&str> = words.iter().map(|w| w.normalized.as_str()).collect();
+        assert_eq!(normalized, vec!["casa", "camion"]);
+        assert_eq!(words[1].original, "camión");
+    }
+
+    #[test]
+    fn test_expand_leet() {
+        assert_eq!(expand_leet("b10g"), vec!["blog", "biog"]);
+        assert_eq!(expand_leet("c0ffee"), vec!["coffee"]);
+        assert_eq!(expand_leet("1337").len(), 2);
+        assert_eq!(expand_leet("11").len(), 4);
+        assert_eq!(expand_leet("shop"), vec!["shop"]);
+    }
+}
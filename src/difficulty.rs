@@ -0,0 +1,356 @@
+use crate::matcher::prefix_set_probability;
+use crate::types::{Config, Quota};
+use std::collections::HashSet;
+
+/// Fracción del objetivo que se considera "seguro" al acotar las integrales
+const CERTAINTY: f64 = 1.0 - 1e-12;
+
+/// Intervalos de la integral del número esperado de intentos
+const INTEGRATION_STEPS: usize = 4096;
+
+/// Un prefijo dentro de su conjunto
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixDifficulty {
+    /// Prefijo
+    pub prefix: String,
+    /// Probabilidad de que una dirección empiece por el prefijo
+    pub probability: f64,
+    /// Probabilidad de que un acierto lleve su etiqueta: empieza por el
+    /// prefijo y por ninguno más largo del conjunto (la etiqueta es el más largo)
+    pub label_probability: f64,
+    /// Prefijo más corto del conjunto por el que ya empieza este
+    pub covered_by: Option<String>,
+}
+
+/// Dificultad de un conjunto de prefijos buscados a la vez
+///
+/// La búsqueda acepta una dirección en cuanto coincide con cualquiera de los
+/// prefijos, así que lo que cuenta es la probabilidad de la unión. Un prefijo
+/// que extiende a otro del conjunto ("abc" con "ab") no la aumenta: solo
+/// cambia la etiqueta con la que se cuentan esos aciertos.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixSet {
+    /// Prefijos sin duplicados, en el orden recibido
+    pub prefixes: Vec<PrefixDifficulty>,
+    /// Probabilidad de que una dirección coincida con alguno
+    pub probability: f64,
+}
+
+impl PrefixSet {
+    /// Analiza un conjunto de prefijos
+    ///
+    /// # Arguments
+    ///
+    /// * `prefixes` - Prefijos buscados (se ignoran los repetidos)
+    pub fn new(prefixes: &[String]) -> Self {
+        let set: HashSet<&str> = prefixes.iter().map(String::as_str).collect();
+        let mut seen = HashSet::new();
+
+        let prefixes = prefixes
+            .iter()
+            .filter(|prefix| seen.insert(prefix.as_str()))
+            .map(|prefix| {
+                let probability = 1.0 / 32f64.powi(prefix.len() as i32);
+                let extensions: HashSet<&str> = set
+                    .iter()
+                    .copied()
+                    .filter(|other| other.len() > prefix.len() && other.starts_with(prefix.as_str()))
+                    .collect();
+
+                PrefixDifficulty {
+                    prefix: prefix.clone(),
+                    probability,
+                    label_probability: (probability - prefix_set_probability(&extensions)).max(0.0),
+                    covered_by: (1..prefix.len())
+                        .map(|len| &prefix[..len])
+                        .find(|shorter| set.contains(shorter))
+                        .map(str::to_string),
+                }
+            })
+            .collect();
+
+        Self {
+            prefixes,
+            probability: prefix_set_probability(&set),
+        }
+    }
+
+    /// Prefijos que no aportan probabilidad porque otro más corto los cubre
+    pub fn covered(&self) -> impl Iterator<Item = &PrefixDifficulty> {
+        self.prefixes.iter().filter(|p| p.covered_by.is_some())
+    }
+
+    /// Probabilidad de un acierto con la etiqueta indicada
+    pub fn label_probability(&self, label: &str) -> Option<f64> {
+        self.prefixes
+            .iter()
+            .find(|p| p.prefix == label)
+            .map(|p| p.label_probability)
+    }
+}
+
+/// Parte de un objetivo: `target` aciertos de un patrón
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalTerm {
+    /// Patrón (un prefijo de cupo o el nombre del matcher)
+    pub label: String,
+    /// Probabilidad de que un intento cuente para este patrón
+    pub probability: f64,
+    /// Aciertos necesarios
+    pub target: usize,
+}
+
+impl GoalTerm {
+    /// Probabilidad de tener ya los aciertos necesarios tras `attempts` intentos
+    ///
+    /// Los aciertos siguen una distribución de Poisson de media
+    /// `attempts * probability` (binomial con p diminuta).
+    pub fn chance_by(&self, attempts: f64) -> f64 {
+        poisson_at_least(attempts * self.probability, self.target)
+    }
+
+    /// Intentos esperados para completar este patrón por sí solo
+    pub fn expected_attempts(&self) -> f64 {
+        if self.target == 0 {
+            return 0.0;
+        }
+        if self.probability <= 0.0 {
+            return f64::INFINITY;
+        }
+        self.target as f64 / self.probability
+    }
+}
+
+/// Condición de parada de una búsqueda: todos sus patrones completos
+///
+/// Sin cupos es un único término (`max_results` aciertos de cualquier
+/// patrón); con cupos, uno por cupo, y la búsqueda termina con el último.
+/// Los cupos se tratan como independientes, lo que es exacto en el límite de
+/// probabilidades pequeñas. Al cubrirse un cupo la búsqueda deja de buscar su
+/// prefijo y sus direcciones pasan a contar para un prefijo más corto, así que
+/// la estimación es algo pesimista con prefijos solapados.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goal {
+    /// Patrones que deben completarse
+    pub terms: Vec<GoalTerm>,
+}
+
+impl Goal {
+    /// Objetivo sin cupos: `target` aciertos de un patrón
+    pub fn results(label: &str, probability: f64, target: usize) -> Self {
+        Self {
+            terms: vec![GoalTerm {
+                label: label.to_string(),
+                probability,
+                target,
+            }],
+        }
+    }
+
+    /// Objetivo con cupos sobre un conjunto de prefijos
+    ///
+    /// # Returns
+    ///
+    /// None si algún cupo no corresponde a un prefijo del conjunto
+    pub fn quotas(set: &PrefixSet, quotas: &[Quota]) -> Option<Self> {
+        let terms = quotas
+            .iter()
+            .map(|quota| {
+                Some(GoalTerm {
+                    label: quota.label.clone(),
+                    probability: set.label_probability(&quota.label)?,
+                    target: quota.target,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { terms })
+    }
+
+    /// Objetivo de una configuración (cupos si los tiene, si no `max_results`)
+    pub fn for_config(config: &Config) -> Option<Self> {
+        if config.quotas.is_empty() {
+            Some(Self::results(
+                config.matcher.name(),
+                config.matcher.probability(),
+                config.max_results,
+            ))
+        } else {
+            Self::quotas(&PrefixSet::new(&config.prefixes), &config.quotas)
+        }
+    }
+
+    /// Indica si el objetivo puede completarse
+    pub fn is_possible(&self) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.target == 0 || term.probability > 0.0)
+    }
+
+    /// Probabilidad de haber completado el objetivo tras `attempts` intentos
+    pub fn chance_by(&self, attempts: f64) -> f64 {
+        self.terms.iter().map(|term| term.chance_by(attempts)).product()
+    }
+
+    /// Intentos necesarios para completar el objetivo con una probabilidad dada
+    ///
+    /// # Arguments
+    ///
+    /// * `confidence` - Probabilidad de éxito deseada (0..1)
+    ///
+    /// # Returns
+    ///
+    /// Número de intentos (infinito si el objetivo es imposible)
+    pub fn attempts_for_confidence(&self, confidence: f64) -> f64 {
+        if !self.is_possible() {
+            return f64::INFINITY;
+        }
+
+        let mut high = self
+            .terms
+            .iter()
+            .map(GoalTerm::expected_attempts)
+            .fold(1.0, f64::max);
+        while self.chance_by(high) < confidence {
+            high *= 2.0;
+        }
+
+        let mut low = 0.0;
+        for _ in 0..200 {
+            let middle = (low + high) / 2.0;
+            if self.chance_by(middle) < confidence {
+                low = middle;
+            } else {
+                high = middle;
+            }
+            if high - low <= high * 1e-12 {
+                break;
+            }
+        }
+        high
+    }
+
+    /// Intentos esperados para completar el objetivo
+    ///
+    /// Es la integral de la probabilidad de no haber terminado aún; con
+    /// varios cupos supera al mayor de los esperados por cupo.
+    pub fn expected_attempts(&self) -> f64 {
+        if !self.is_possible() {
+            return f64::INFINITY;
+        }
+        if let [term] = self.terms.as_slice() {
+            return term.expected_attempts();
+        }
+
+        // Regla de Simpson sobre [0, intentos con certeza práctica]
+        let end = self.attempts_for_confidence(CERTAINTY);
+        let step = end / INTEGRATION_STEPS as f64;
+        let pending = |n: f64| 1.0 - self.chance_by(n);
+        let inner: f64 = (1..INTEGRATION_STEPS)
+            .map(|i| {
+                let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+                weight * pending(i as f64 * step)
+            })
+            .sum();
+        (pending(0.0) + inner + pending(end)) * step / 3.0
+    }
+}
+
+/// Probabilidad de que una Poisson de media `mean` valga al menos `count`
+fn poisson_at_least(mean: f64, count: usize) -> f64 {
+    if count == 0 {
+        return 1.0;
+    }
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    if count == 1 {
+        return -(-mean).exp_m1();
+    }
+
+    // P(X < count) sumando los términos en escala logarítmica
+    let ln_mean = mean.ln();
+    let mut ln_term = -mean;
+    let mut below = 0.0;
+    for k in 0..count {
+        below += ln_term.exp();
+        ln_term += ln_mean - ((k + 1) as f64).ln();
+    }
+    (1.0 - below).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::attempts_for_confidence;
+
+    fn prefixes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_prefix_set() {
+        let set = PrefixSet::new(&prefixes(&["ab", "abc", "cd", "ab"]));
+        assert_eq!(set.prefixes.len(), 3);
+
+        // "abc" no añade nada a la unión de "ab" y "cd"
+        assert!((set.probability - 2.0 / 1024.0).abs() < 1e-15);
+        let covered: Vec<_> = set.covered().map(|p| p.prefix.as_str()).collect();
+        assert_eq!(covered, vec!["abc"]);
+        assert_eq!(set.prefixes[1].covered_by.as_deref(), Some("ab"));
+
+        // Las direcciones "abc..." llevan la etiqueta "abc", no "ab"
+        let ab = set.label_probability("ab").unwrap();
+        assert!((ab - (1.0 / 1024.0 - 1.0 / 32768.0)).abs() < 1e-15);
+        assert_eq!(set.label_probability("abc"), Some(1.0 / 32768.0));
+        assert_eq!(set.label_probability("zz"), None);
+    }
+
+    #[test]
+    fn test_goal_single_term() {
+        // Un resultado: coincide con la distribución geométrica
+        let p = 1.0 / 32f64.powi(4);
+        let goal = Goal::results("prefijo abcd", p, 1);
+        assert_eq!(goal.expected_attempts(), 32f64.powi(4));
+        for confidence in [0.5, 0.9, 0.99] {
+            let exact = attempts_for_confidence(p, confidence);
+            let approx = goal.attempts_for_confidence(confidence);
+            assert!((approx / exact - 1.0).abs() < 1e-4, "{} vs {}", approx, exact);
+        }
+
+        // Tres resultados: la mediana de una Poisson con media 3/p está cerca de 2.67/p
+        let goal = Goal::results("prefijo abcd", p, 3);
+        assert_eq!(goal.expected_attempts(), 3.0 / p);
+        let median = goal.attempts_for_confidence(0.5) * p;
+        assert!((median - 2.674).abs() < 1e-2, "{}", median);
+
+        assert!(!Goal::results("imposible", 0.0, 1).is_possible());
+        assert!(Goal::results("imposible", 0.0, 1).expected_attempts().is_infinite());
+    }
+
+    #[test]
+    fn test_goal_quotas() {
+        let set = PrefixSet::new(&prefixes(&["ab", "cd"]));
+        let quota = |label: &str, target| Quota {
+            label: label.to_string(),
+            target,
+        };
+
+        // Dos cupos iguales: esperar a ambos cuesta más que a cualquiera de ellos
+        let goal = Goal::quotas(&set, &[quota("ab", 1), quota("cd", 1)]).unwrap();
+        let single = 1024.0;
+        let expected = goal.expected_attempts();
+        assert!((expected / single - 1.5).abs() < 1e-3, "{}", expected);
+        assert!(goal.chance_by(single) < goal.terms[0].chance_by(single));
+
+        // Un cupo sin prefijo en el conjunto no se puede estimar
+        assert!(Goal::quotas(&set, &[quota("zz", 1)]).is_none());
+    }
+
+    #[test]
+    fn test_poisson_at_least() {
+        assert_eq!(poisson_at_least(5.0, 0), 1.0);
+        assert_eq!(poisson_at_least(0.0, 3), 0.0);
+        assert!((poisson_at_least(2.0, 2) - (1.0 - 3.0 * (-2.0f64).exp())).abs() < 1e-12);
+        assert!(poisson_at_least(10_000.0, 3) > 0.999_999);
+    }
+}
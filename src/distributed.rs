@@ -0,0 +1,1530 @@
+use crate::error::{Result, VanityError};
+use crate::generator::public_key_from_secret;
+use crate::matcher::{AnyMatcher, Candidate, Matcher, PrefixMatcher, RegexMatcher, SuffixMatcher};
+use crate::reporter::{ProgressReport, SearchObserver};
+use crate::search::search_vanity;
+use crate::searcher::Searcher;
+use crate::types::{CancelToken, Config, SearchStats, VanityResult};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use crossbeam::channel::{unbounded, RecvTimeoutError, Sender};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Versión del protocolo entre coordinador y workers
+///
+/// Se anuncia en el handshake; un extremo con otra versión es rechazado.
+/// La versión 3 sella todos los mensajes tras el handshake con
+/// ChaCha20-Poly1305 y claves de la conexión derivadas con HKDF.
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Longitud mínima de la clave compartida
+pub const MIN_KEY_LENGTH: usize = 16;
+
+/// Cada cuánto informa un worker de sus intentos
+const REPORT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Tiempo máximo para completar el handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Tiempo máximo para escribir un mensaje a un worker; uno que no lee no
+/// puede bloquear al coordinador
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Conexiones abiertas a la vez que acepta un coordinador por defecto
+pub const MAX_CONNECTIONS: usize = 256;
+
+/// Espera máxima entre reconexiones de un worker
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Tamaño máximo de un mensaje (una línea JSON; los mensajes de sesión van
+/// en hexadecimal y ocupan el doble)
+const MAX_MESSAGE_BYTES: u64 = 256 * 1024;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Búsqueda que el coordinador reparte entre los workers
+///
+/// El matcher no es serializable: se envían los patrones y cada worker lo
+/// reconstruye con `Job::matcher`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    /// Prefijos a buscar
+    pub prefixes: Vec<String>,
+    /// Sufijos a buscar (ya descartados los imposibles)
+    pub suffixes: Vec<String>,
+    /// Expresiones regulares a buscar
+    pub regexes: Vec<String>,
+    /// Número de resultados que termina la búsqueda
+    pub max_results: usize,
+    /// Intentos totales (de todos los workers) que terminan la búsqueda
+    pub max_attempts: Option<u64>,
+    /// Momento en que el coordinador detiene a todos (`--max-time`/`--until`)
+    ///
+    /// No viaja a los workers: el coordinador les envía Stop al llegar.
+    #[serde(skip)]
+    pub deadline: Option<Instant>,
+}
+
+impl Job {
+    /// Construye el matcher de los patrones del trabajo
+    pub fn matcher(&self) -> std::result::Result<Arc<dyn Matcher>, String> {
+        let mut matchers: Vec<Arc<dyn Matcher>> = Vec::new();
+        if !self.prefixes.is_empty() {
+            matchers.push(Arc::new(PrefixMatcher::new(&self.prefixes)?));
+        }
+        if !self.suffixes.is_empty() {
+            matchers.push(Arc::new(SuffixMatcher::new(&self.suffixes)?));
+        }
+        for pattern in &self.regexes {
+            matchers.push(Arc::new(RegexMatcher::new(pattern)?));
+        }
+
+        match matchers.len() {
+            0 => Err("El trabajo no tiene ningún patrón de búsqueda".to_string()),
+            1 => Ok(matchers.remove(0)),
+            _ => Ok(Arc::new(AnyMatcher::new(matchers))),
+        }
+    }
+}
+
+/// Resultado tal como viaja por la red
+///
+/// Solo se envía la clave secreta: el coordinador recalcula la clave
+/// pública, la dirección y el patrón, así que no depende de lo que diga el
+/// worker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WireResult {
+    /// Clave secreta expandida en hexadecimal (64 bytes)
+    pub private_key: String,
+    /// Momento del hallazgo (RFC 3339)
+    pub found_at: String,
+}
+
+impl From<&VanityResult> for WireResult {
+    fn from(result: &VanityResult) -> Self {
+        Self {
+            private_key: hex::encode(result.private_key),
+            found_at: result.timestamp.to_rfc3339(),
+        }
+    }
+}
+
+/// Mensajes del protocolo (una línea JSON por mensaje)
+///
+/// Handshake: el worker envía `Hello` con un nonce; el coordinador responde
+/// `Challenge` probando que conoce la clave y con su propio nonce; el worker
+/// responde `Auth` y recibe `Job` (o `Stop` si la búsqueda ya terminó). Luego
+/// el worker envía `Progress` y `Found` hasta recibir `Stop`.
+///
+/// Solo `Hello`, `Challenge`, `Auth` y `Rejected` viajan en claro; a partir
+/// de `Job` cada mensaje va sellado por la `Session` de la conexión.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// Worker → coordinador: presentación
+    Hello {
+        version: u32,
+        worker: String,
+        nonce: String,
+    },
+    /// Coordinador → worker: reto y prueba de conocer la clave
+    Challenge {
+        version: u32,
+        nonce: String,
+        mac: String,
+    },
+    /// Worker → coordinador: respuesta al reto
+    Auth { mac: String },
+    /// Coordinador → worker: búsqueda a realizar
+    Job { job: Job },
+    /// Coordinador → worker: conexión rechazada
+    Rejected { reason: String },
+    /// Worker → coordinador: intentos desde el último informe
+    Progress { attempts: u64 },
+    /// Worker → coordinador: resultado encontrado
+    Found { result: WireResult },
+    /// Coordinador → worker: la búsqueda ha terminado
+    Stop,
+}
+
+/// Escribe un mensaje en la conexión
+fn send_message<T: Serialize>(stream: &mut impl Write, message: &T) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Lee el siguiente mensaje de la conexión (None si el otro extremo la cerró)
+fn receive_message<T: DeserializeOwned>(reader: &mut impl BufRead) -> Result<Option<T>> {
+    let mut line = String::new();
+    let read = reader.take(MAX_MESSAGE_BYTES).read_line(&mut line)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') {
+        return Err(VanityError::InvalidConfig(
+            "Mensaje demasiado largo o incompleto".to_string(),
+        ));
+    }
+    Ok(Some(serde_json::from_str(&line)?))
+}
+
+/// Nonce aleatorio de 32 bytes en hexadecimal
+fn new_nonce() -> String {
+    let mut nonce = [0u8; 32];
+    OsRng.fill_bytes(&mut nonce);
+    hex::encode(nonce)
+}
+
+/// HMAC-SHA256 de varias partes, cada una precedida de su longitud
+fn keyed_mac(key: &[u8], parts: &[&[u8]]) -> HmacSha256 {
+    let mut mac =
+        <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC admite claves de cualquier longitud");
+    for part in parts {
+        mac.update(&(part.len() as u64).to_le_bytes());
+        mac.update(part);
+    }
+    mac
+}
+
+/// Firma las partes con la clave compartida (hexadecimal)
+fn sign(key: &[u8], parts: &[&[u8]]) -> String {
+    hex::encode(keyed_mac(key, parts).finalize().into_bytes())
+}
+
+/// Comprueba una firma en tiempo constante
+fn verify(key: &[u8], parts: &[&[u8]], mac: &str) -> bool {
+    match hex::decode(mac) {
+        Ok(mac) => keyed_mac(key, parts).verify_slice(&mac).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Mensaje de una sesión autenticada tal como viaja por la red
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SealedMessage {
+    /// Número de secuencia dentro de su sentido de la conexión
+    seq: u64,
+    /// `Message` en JSON sellado con ChaCha20-Poly1305, en hexadecimal
+    payload: String,
+}
+
+/// Claves de una conexión, derivadas con HKDF-SHA256 de la clave compartida
+/// y de los nonces del handshake (distintas en cada conexión y sentido)
+struct SessionKeys {
+    /// Clave de los mensajes del coordinador
+    coordinator: Key,
+    /// Clave de los mensajes del worker
+    worker: Key,
+}
+
+impl SessionKeys {
+    fn derive(key: &[u8], worker_nonce: &str, nonce: &str, worker: &str) -> Arc<Self> {
+        let salt = length_prefixed(&[worker_nonce.as_bytes(), nonce.as_bytes()]);
+        let hkdf = Hkdf::<Sha256>::new(Some(&salt), key);
+        let expand = |sender: &[u8]| {
+            let info = length_prefixed(&[b"torge-forge session", sender, worker.as_bytes()]);
+            let mut okm = Key::default();
+            hkdf.expand(&info, &mut okm)
+                .expect("32 bytes es una longitud válida para HKDF-SHA256");
+            okm
+        };
+        Arc::new(Self {
+            coordinator: expand(COORDINATOR),
+            worker: expand(WORKER),
+        })
+    }
+}
+
+/// Concatena las partes, cada una precedida de su longitud
+fn length_prefixed(parts: &[&[u8]]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for part in parts {
+        data.extend_from_slice(&(part.len() as u64).to_le_bytes());
+        data.extend_from_slice(part);
+    }
+    data
+}
+
+/// Un sentido de una conexión autenticada
+///
+/// Cada mensaje se sella con ChaCha20-Poly1305 usando su número de secuencia
+/// como nonce y el emisor como datos asociados: el receptor rechaza mensajes
+/// alterados, inyectados, repetidos o desordenados y cierra la conexión.
+struct Channel {
+    cipher: ChaCha20Poly1305,
+    /// Emisor de los mensajes de este sentido
+    sender: &'static [u8],
+    /// Número de secuencia del próximo mensaje
+    seq: u64,
+}
+
+impl Channel {
+    fn new(keys: &Arc<SessionKeys>, sender: &'static [u8]) -> Self {
+        let key = match sender {
+            COORDINATOR => &keys.coordinator,
+            _ => &keys.worker,
+        };
+        Self {
+            cipher: ChaCha20Poly1305::new(key),
+            sender,
+            seq: 0,
+        }
+    }
+
+    /// Nonce del mensaje `seq` (único por clave: cada sentido tiene la suya)
+    fn nonce(seq: u64) -> Nonce {
+        let mut nonce = Nonce::default();
+        nonce[..8].copy_from_slice(&seq.to_le_bytes());
+        nonce
+    }
+
+    /// Sella y envía el siguiente mensaje de este sentido
+    fn send(&mut self, stream: &mut impl Write, message: &Message) -> Result<()> {
+        let plaintext = serde_json::to_vec(message)?;
+        let payload = Payload {
+            msg: &plaintext,
+            aad: self.sender,
+        };
+        let ciphertext = self
+            .cipher
+            .encrypt(&Self::nonce(self.seq), payload)
+            .map_err(|_| VanityError::Crypto("no se pudo sellar el mensaje".to_string()))?;
+        let sealed = SealedMessage {
+            seq: self.seq,
+            payload: hex::encode(ciphertext),
+        };
+        self.seq += 1;
+        send_message(stream, &sealed)
+    }
+
+    /// Recibe y comprueba el siguiente mensaje (None si la conexión se cerró)
+    fn receive(&mut self, reader: &mut impl BufRead) -> Result<Option<Message>> {
+        let Some(sealed) = receive_message::<SealedMessage>(reader)? else {
+            return Ok(None);
+        };
+        let ciphertext = hex::decode(&sealed.payload)
+            .map_err(|_| VanityError::Crypto("mensaje de sesión mal formado".to_string()))?;
+        let payload = Payload {
+            msg: &ciphertext,
+            aad: self.sender,
+        };
+        let plaintext = match sealed.seq == self.seq {
+            true => self.cipher.decrypt(&Self::nonce(self.seq), payload).ok(),
+            false => None,
+        }
+        .ok_or_else(|| {
+            VanityError::Crypto(format!(
+                "mensaje {} rechazado: autenticación o número de secuencia incorrectos",
+                sealed.seq
+            ))
+        })?;
+
+        self.seq += 1;
+        Ok(Some(serde_json::from_slice(&plaintext)?))
+    }
+}
+
+/// Emisor de los mensajes del coordinador
+const COORDINATOR: &[u8] = b"coordinator";
+
+/// Emisor de los mensajes de los workers
+const WORKER: &[u8] = b"worker";
+
+/// Lee la clave compartida de un fichero (sin espacios al principio ni al final)
+///
+/// # Arguments
+///
+/// * `path` - Fichero con la clave
+///
+/// # Returns
+///
+/// La clave, o un error si no se puede leer o es demasiado corta
+pub fn load_cluster_key(path: &Path) -> Result<Vec<u8>> {
+    let content = std::fs::read(path).map_err(|e| {
+        VanityError::InvalidConfig(format!(
+            "No se pudo leer la clave compartida {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let key = content.trim_ascii().to_vec();
+
+    if key.len() < MIN_KEY_LENGTH {
+        return Err(VanityError::InvalidConfig(format!(
+            "La clave compartida debe tener al menos {} bytes",
+            MIN_KEY_LENGTH
+        )));
+    }
+    Ok(key)
+}
+
+/// Reconstruye un resultado a partir de su clave secreta
+///
+/// # Arguments
+///
+/// * `wire` - Resultado recibido de un worker
+/// * `matcher` - Matcher de la búsqueda del coordinador
+///
+/// # Returns
+///
+/// El resultado verificado, o un error si la clave no es válida o su
+/// dirección no cumple el matcher
+pub fn verify_result(
+    wire: &WireResult,
+    matcher: &dyn Matcher,
+) -> std::result::Result<VanityResult, String> {
+    let private_key: [u8; 64] = hex::decode(&wire.private_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| "clave secreta mal formada".to_string())?;
+    let timestamp = chrono::DateTime::parse_from_rfc3339(&wire.found_at)
+        .map_err(|_| format!("fecha '{}' inválida", wire.found_at))?
+        .with_timezone(&chrono::Utc);
+
+    let public_key = public_key_from_secret(&private_key);
+    let candidate = Candidate::new(&public_key);
+    let matched_prefix = matcher
+        .matches(&candidate)
+        .ok_or_else(|| format!("{} no cumple el patrón de la búsqueda", candidate.address()))?;
+
+    Ok(VanityResult {
+        address: candidate.into_address(),
+        dictionary_word: matcher.dictionary_word(&matched_prefix),
+        matched_prefix,
+        private_key,
+        public_key,
+        timestamp,
+    })
+}
+
+/// Eventos de una búsqueda distribuida vistos desde el coordinador
+#[derive(Debug)]
+pub enum CoordinatorEvent {
+    /// Un worker se autenticó y recibió el trabajo
+    Connected { worker: String },
+    /// Un worker se desconectó (puede volver a conectarse)
+    Disconnected { worker: String },
+    /// Se rechazó una conexión (versión o clave incorrectas)
+    Rejected { peer: String, reason: String },
+    /// Un worker envió un resultado que no supera la verificación
+    InvalidResult { worker: String, reason: String },
+    /// Un worker informó de sus intentos; incluye las estadísticas globales
+    Progress { stats: SearchStats },
+    /// Resultado verificado
+    Found {
+        worker: String,
+        result: VanityResult,
+    },
+}
+
+/// Mensajes de los hilos de conexión al hilo principal del coordinador
+enum Incoming {
+    Connected(String),
+    Disconnected(String),
+    Rejected(String, String),
+    Progress(u64),
+    Found(String, WireResult),
+}
+
+/// Coordinador de una búsqueda distribuida
+///
+/// Reparte el mismo `Job` a todos los workers que se autentican, suma sus
+/// intentos, verifica sus resultados y los detiene a todos al alcanzar
+/// `max_results` (o `max_attempts`, o al pasar `deadline`). Los workers pueden conectarse y
+/// reconectarse en cualquier momento mientras la búsqueda sigue abierta.
+///
+/// Tras el handshake los mensajes van cifrados y autenticados con claves de
+/// la conexión. La seguridad depende de la clave compartida: cualquiera que
+/// la tenga puede hacerse pasar por worker (y recibir el trabajo) o por
+/// coordinador; guárdese como una clave privada más.
+pub struct Coordinator {
+    /// Socket de escucha
+    listener: TcpListener,
+    /// Clave compartida con los workers
+    key: Arc<Vec<u8>>,
+    /// Búsqueda a repartir
+    job: Job,
+    /// Matcher con el que se verifican los resultados
+    matcher: Arc<dyn Matcher>,
+    /// Conexiones abiertas a la vez (cada una con su hilo)
+    max_connections: usize,
+}
+
+impl Coordinator {
+    /// Abre el socket de escucha del coordinador
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Dirección de escucha (por ejemplo `0.0.0.0:7878`)
+    /// * `key` - Clave compartida con los workers
+    /// * `job` - Búsqueda a repartir
+    pub fn bind(addr: &str, key: &[u8], job: Job) -> Result<Self> {
+        if key.len() < MIN_KEY_LENGTH {
+            return Err(VanityError::InvalidConfig(format!(
+                "La clave compartida debe tener al menos {} bytes",
+                MIN_KEY_LENGTH
+            )));
+        }
+        if job.max_results == 0 {
+            return Err(VanityError::InvalidConfig(
+                "El trabajo debe pedir al menos 1 resultado".to_string(),
+            ));
+        }
+        let matcher = job.matcher().map_err(VanityError::InvalidConfig)?;
+
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            key: Arc::new(key.to_vec()),
+            job,
+            matcher,
+            max_connections: MAX_CONNECTIONS,
+        })
+    }
+
+    /// Cambia el número de conexiones abiertas a la vez
+    ///
+    /// Las que llegan por encima del límite se cierran sin handshake; el
+    /// worker vuelve a intentarlo con su espera entre reconexiones.
+    pub fn with_max_connections(mut self, limit: usize) -> Self {
+        self.max_connections = limit.max(1);
+        self
+    }
+
+    /// Dirección en la que escucha el coordinador
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Atiende a los workers hasta completar la búsqueda
+    ///
+    /// # Arguments
+    ///
+    /// * `running` - Se deja de coordinar cuando pasa a `false` (Ctrl+C)
+    /// * `callback` - Recibe los eventos de la búsqueda; si devuelve un error
+    ///   se detiene a todos los workers y se propaga
+    ///
+    /// # Returns
+    ///
+    /// Estadísticas globales de la búsqueda
+    pub fn run<F>(self, running: &AtomicBool, mut callback: F) -> Result<SearchStats>
+    where
+        F: FnMut(CoordinatorEvent) -> Result<()>,
+    {
+        let start_time = Instant::now();
+        let done = Arc::new(AtomicBool::new(false));
+        let connections: Arc<Mutex<HashMap<u64, (TcpStream, Channel)>>> = Arc::default();
+        let (tx, rx) = unbounded::<Incoming>();
+
+        // Hilo que acepta conexiones; cada worker tiene su propio hilo, hasta
+        // `max_connections` a la vez
+        self.listener.set_nonblocking(true)?;
+        let acceptor = {
+            let done = Arc::clone(&done);
+            let connections = Arc::clone(&connections);
+            let key = Arc::clone(&self.key);
+            let job = self.job.clone();
+            let listener = self.listener;
+            let max_connections = self.max_connections;
+
+            std::thread::spawn(move || {
+                let next_id = AtomicU64::new(0);
+                let active = Arc::new(AtomicUsize::new(0));
+                while !done.load(Ordering::SeqCst) {
+                    match listener.accept() {
+                        Ok((stream, peer)) if active.load(Ordering::SeqCst) >= max_connections => {
+                            drop(stream);
+                            let _ = tx.send(Incoming::Rejected(
+                                peer.to_string(),
+                                format!(
+                                    "demasiadas conexiones abiertas (máximo {})",
+                                    max_connections
+                                ),
+                            ));
+                        }
+                        Ok((stream, peer)) => {
+                            let connection = Connection {
+                                id: next_id.fetch_add(1, Ordering::Relaxed),
+                                peer,
+                                key: Arc::clone(&key),
+                                job: job.clone(),
+                                tx: tx.clone(),
+                                connections: Arc::clone(&connections),
+                                done: Arc::clone(&done),
+                            };
+                            let active = Arc::clone(&active);
+                            active.fetch_add(1, Ordering::SeqCst);
+                            std::thread::spawn(move || {
+                                connection.serve(stream);
+                                active.fetch_sub(1, Ordering::SeqCst);
+                            });
+                        }
+                        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                            std::thread::sleep(Duration::from_millis(50));
+                        }
+                        Err(_) => std::thread::sleep(Duration::from_millis(50)),
+                    }
+                }
+            })
+        };
+
+        let mut stats = SearchStats::default();
+        let mut seen = HashSet::new();
+        let mut outcome = Ok(());
+        while running.load(Ordering::Relaxed) {
+            if self
+                .job
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                break;
+            }
+            let event = match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(incoming) => match incoming {
+                    Incoming::Connected(worker) => CoordinatorEvent::Connected { worker },
+                    Incoming::Disconnected(worker) => CoordinatorEvent::Disconnected { worker },
+                    Incoming::Rejected(peer, reason) => CoordinatorEvent::Rejected { peer, reason },
+                    Incoming::Progress(attempts) => {
+                        stats.total_attempts += attempts;
+                        stats.elapsed_seconds = start_time.elapsed().as_secs_f64();
+                        CoordinatorEvent::Progress {
+                            stats: stats.clone(),
+                        }
+                    }
+                    Incoming::Found(worker, wire) => match verify_result(&wire, &*self.matcher) {
+                        Ok(result) if !seen.insert(result.public_key) => {
+                            CoordinatorEvent::InvalidResult {
+                                worker,
+                                reason: format!("{} ya se había recibido", result.address),
+                            }
+                        }
+                        Ok(result) => {
+                            stats.results_found += 1;
+                            CoordinatorEvent::Found { worker, result }
+                        }
+                        Err(reason) => CoordinatorEvent::InvalidResult { worker, reason },
+                    },
+                },
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            if let Err(e) = callback(event) {
+                outcome = Err(e);
+                break;
+            }
+
+            let attempts_reached = self
+                .job
+                .max_attempts
+                .is_some_and(|max| stats.total_attempts >= max);
+            if stats.results_found >= self.job.max_results || attempts_reached {
+                break;
+            }
+        }
+
+        // Detener a todos los workers conectados (los que lleguen después
+        // reciben Stop al autenticarse). Se envía fuera del registro y a la
+        // vez a todos: un worker atascado solo retrasa su propio Stop
+        done.store(true, Ordering::SeqCst);
+        let stopping: Vec<_> = connections.lock().unwrap().drain().collect();
+        let mut pending = stopping.len();
+        std::thread::scope(|scope| {
+            for (_, (mut stream, mut channel)) in stopping {
+                scope.spawn(move || {
+                    let _ = channel.send(&mut stream, &Message::Stop);
+                });
+            }
+        });
+        let _ = acceptor.join();
+
+        // Recoger el último informe de intentos de cada worker: cancelan su
+        // búsqueda, informan y cierran la conexión
+        let grace = Instant::now() + REPORT_INTERVAL + Duration::from_secs(1);
+        while Instant::now() < grace && pending > 0 {
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(Incoming::Progress(attempts)) => stats.total_attempts += attempts,
+                Ok(Incoming::Disconnected(_)) => pending -= 1,
+                _ => {}
+            }
+        }
+        for incoming in rx.try_iter() {
+            if let Incoming::Progress(attempts) = incoming {
+                stats.total_attempts += attempts;
+            }
+        }
+
+        if !running.load(Ordering::Relaxed) && outcome.is_ok() {
+            outcome = Err(VanityError::Cancelled);
+        }
+        outcome?;
+
+        stats.elapsed_seconds = start_time.elapsed().as_secs_f64();
+        Ok(stats)
+    }
+}
+
+/// Conexión de un worker con el coordinador
+struct Connection {
+    id: u64,
+    peer: SocketAddr,
+    key: Arc<Vec<u8>>,
+    job: Job,
+    tx: Sender<Incoming>,
+    connections: Arc<Mutex<HashMap<u64, (TcpStream, Channel)>>>,
+    done: Arc<AtomicBool>,
+}
+
+impl Connection {
+    /// Autentica al worker, le envía el trabajo y reenvía sus mensajes
+    fn serve(self, stream: TcpStream) {
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+        let mut reader = BufReader::new(stream);
+        let _ = writer.set_nonblocking(false);
+        let _ = writer.set_read_timeout(Some(HANDSHAKE_TIMEOUT));
+        let _ = writer.set_write_timeout(Some(WRITE_TIMEOUT));
+
+        let (worker, keys) = match self.handshake(&mut reader, &mut writer) {
+            Ok(session) => session,
+            Err(reason) => {
+                let _ = send_message(
+                    &mut writer,
+                    &Message::Rejected {
+                        reason: reason.clone(),
+                    },
+                );
+                let _ = self
+                    .tx
+                    .send(Incoming::Rejected(self.peer.to_string(), reason));
+                return;
+            }
+        };
+
+        // Con el registro bloqueado: o bien el coordinador ya terminó y se
+        // envía Stop, o bien se envía el trabajo y el Stop llegará después
+        let mut outgoing = Channel::new(&keys, COORDINATOR);
+        let sent_job = {
+            let mut connections = self.connections.lock().unwrap();
+            if self.done.load(Ordering::SeqCst) {
+                let _ = outgoing.send(&mut writer, &Message::Stop);
+                false
+            } else {
+                let job = Message::Job {
+                    job: self.job.clone(),
+                };
+                let sent = outgoing.send(&mut writer, &job).is_ok();
+                match writer.try_clone() {
+                    Ok(clone) if sent => connections.insert(self.id, (clone, outgoing)).is_none(),
+                    _ => false,
+                }
+            }
+        };
+        if sent_job {
+            let _ = writer.set_read_timeout(None);
+            let _ = self.tx.send(Incoming::Connected(worker.clone()));
+            self.forward(&mut reader, &mut Channel::new(&keys, WORKER), &worker);
+            let _ = self.tx.send(Incoming::Disconnected(worker));
+        }
+
+        self.connections.lock().unwrap().remove(&self.id);
+    }
+
+    /// Handshake del lado del coordinador; devuelve el nombre del worker y
+    /// las claves de la conexión
+    fn handshake(
+        &self,
+        reader: &mut impl BufRead,
+        writer: &mut impl Write,
+    ) -> std::result::Result<(String, Arc<SessionKeys>), String> {
+        let (worker, worker_nonce) = match receive_message(reader) {
+            Ok(Some(Message::Hello {
+                version,
+                worker,
+                nonce,
+            })) => {
+                if version != PROTOCOL_VERSION {
+                    return Err(format!(
+                        "Versión de protocolo {} no soportada (se esperaba {})",
+                        version, PROTOCOL_VERSION
+                    ));
+                }
+                (worker, nonce)
+            }
+            Ok(_) => return Err("Se esperaba un saludo (hello)".to_string()),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let nonce = new_nonce();
+        let challenge = Message::Challenge {
+            version: PROTOCOL_VERSION,
+            mac: sign(
+                &self.key,
+                &[COORDINATOR, worker_nonce.as_bytes(), nonce.as_bytes()],
+            ),
+            nonce: nonce.clone(),
+        };
+        send_message(writer, &challenge).map_err(|e| e.to_string())?;
+
+        let parts: [&[u8]; 4] = [
+            WORKER,
+            nonce.as_bytes(),
+            worker_nonce.as_bytes(),
+            worker.as_bytes(),
+        ];
+        match receive_message(reader) {
+            Ok(Some(Message::Auth { mac })) if verify(&self.key, &parts, &mac) => {
+                let keys = SessionKeys::derive(&self.key, &worker_nonce, &nonce, &worker);
+                Ok((worker, keys))
+            }
+            Ok(Some(Message::Auth { .. })) => Err("Autenticación fallida".to_string()),
+            Ok(None) => {
+                Err("El worker cerró la conexión ante el reto (¿clave distinta?)".to_string())
+            }
+            Ok(_) => Err("Se esperaba la respuesta al reto (auth)".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Reenvía al hilo principal los mensajes de un worker autenticado; un
+    /// mensaje que no supera la autenticación corta la conexión
+    fn forward(&self, reader: &mut impl BufRead, incoming: &mut Channel, worker: &str) {
+        while let Ok(Some(message)) = incoming.receive(reader) {
+            let incoming = match message {
+                Message::Progress { attempts } => Incoming::Progress(attempts),
+                Message::Found { result } => Incoming::Found(worker.to_string(), result),
+                _ => continue,
+            };
+            if self.tx.send(incoming).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Opciones de un worker de búsqueda distribuida
+#[derive(Debug, Clone)]
+pub struct WorkerOptions {
+    /// Dirección del coordinador (`host:puerto`)
+    pub coordinator: String,
+    /// Clave compartida con el coordinador
+    pub key: Vec<u8>,
+    /// Nombre con el que se presenta el worker
+    pub name: String,
+    /// Número de hilos de búsqueda
+    pub threads: usize,
+}
+
+/// Eventos de una búsqueda distribuida vistos desde un worker
+#[derive(Debug)]
+pub enum WorkerEvent {
+    /// Conectado y autenticado; el coordinador envió el trabajo
+    Connected { job: Job },
+    /// Resultado enviado al coordinador
+    Found { address: String },
+    /// Se perdió la conexión; se reintentará tras la espera indicada
+    Disconnected { reason: String, retry_in: Duration },
+}
+
+/// Cómo terminó una sesión con el coordinador
+enum SessionEnd {
+    /// El coordinador dio la búsqueda por terminada
+    Stopped,
+    /// El usuario detuvo el worker
+    Cancelled,
+}
+
+/// Trabaja para un coordinador hasta que este dé la búsqueda por terminada
+///
+/// Si la conexión se pierde, el worker vuelve a conectarse con esperas
+/// crecientes (hasta `MAX_RECONNECT_DELAY`) y reenvía los resultados que no
+/// pudo entregar. Un rechazo del coordinador (clave o versión incorrectas)
+/// no se reintenta.
+///
+/// # Arguments
+///
+/// * `options` - Coordinador, clave, nombre e hilos del worker
+/// * `running` - El worker se detiene cuando pasa a `false` (Ctrl+C)
+/// * `on_event` - Recibe los eventos de la conexión
+///
+/// # Returns
+///
+/// Intentos realizados y resultados entregados por este worker
+pub fn run_worker<F>(
+    options: &WorkerOptions,
+    running: &AtomicBool,
+    mut on_event: F,
+) -> Result<SearchStats>
+where
+    F: FnMut(WorkerEvent),
+{
+    let start_time = Instant::now();
+    let mut stats = SearchStats::default();
+    let mut pending: Vec<WireResult> = Vec::new();
+    let mut delay = Duration::from_secs(1);
+
+    while running.load(Ordering::Relaxed) {
+        let mut connected = false;
+        let outcome = worker_session(options, running, &mut stats, &mut pending, &mut |event| {
+            if let WorkerEvent::Connected { .. } = event {
+                connected = true;
+            }
+            on_event(event);
+        });
+
+        match outcome {
+            Ok(SessionEnd::Stopped) => {
+                stats.elapsed_seconds = start_time.elapsed().as_secs_f64();
+                return Ok(stats);
+            }
+            Ok(SessionEnd::Cancelled) => break,
+            Err(e @ VanityError::InvalidConfig(_)) => return Err(e),
+            Err(e) => {
+                if connected {
+                    delay = Duration::from_secs(1);
+                }
+                on_event(WorkerEvent::Disconnected {
+                    reason: e.to_string(),
+                    retry_in: delay,
+                });
+
+                let retry_at = Instant::now() + delay;
+                while running.load(Ordering::Relaxed) && Instant::now() < retry_at {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        }
+    }
+
+    Err(VanityError::Cancelled)
+}
+
+/// Una conexión completa con el coordinador: handshake, búsqueda e informes
+fn worker_session(
+    options: &WorkerOptions,
+    running: &AtomicBool,
+    stats: &mut SearchStats,
+    pending: &mut Vec<WireResult>,
+    on_event: &mut dyn FnMut(WorkerEvent),
+) -> Result<SessionEnd> {
+    let addr = options
+        .coordinator
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::other("dirección del coordinador sin resolver"))?;
+    let mut writer = TcpStream::connect_timeout(&addr, HANDSHAKE_TIMEOUT)?;
+    writer.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let mut reader = BufReader::new(writer.try_clone()?);
+
+    let (job, mut incoming, mut outgoing) = worker_handshake(options, &mut reader, &mut writer)?;
+    let Some(job) = job else {
+        return Ok(SessionEnd::Stopped);
+    };
+    writer.set_read_timeout(None)?;
+    let matcher = job.matcher().map_err(VanityError::InvalidConfig)?;
+    on_event(WorkerEvent::Connected { job: job.clone() });
+
+    // Entregar lo que quedó pendiente de una conexión anterior
+    while let Some(result) = pending.first() {
+        outgoing.send(
+            &mut writer,
+            &Message::Found {
+                result: result.clone(),
+            },
+        )?;
+        pending.remove(0);
+    }
+
+    // Una sola búsqueda para todo el trabajo: el hilo lector la cancela al
+    // recibir Stop o al cerrarse la conexión
+    let cancel = CancelToken::new();
+    // Los resultados y los informes del hilo de progreso llegan a este hilo,
+    // el único que escribe en la conexión. El coordinador decide cuándo
+    // termina la búsqueda, así que aquí no hay límite de resultados
+    let (updates_tx, updates) = unbounded::<WorkerUpdate>();
+    let searcher = Searcher::new()
+        .matcher(matcher)
+        .threads(options.threads)
+        .max_results(usize::MAX)
+        .progress_interval(REPORT_INTERVAL)
+        .observer(Arc::new(AttemptRelay(updates_tx.clone())));
+    let config = Config {
+        prefixes: job.prefixes.clone(),
+        cancel: cancel.clone(),
+        ..searcher.config()?
+    };
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let reader_handle = {
+        let cancel = cancel.clone();
+        let stopped = Arc::clone(&stopped);
+        std::thread::spawn(move || {
+            while let Ok(Some(message)) = incoming.receive(&mut reader) {
+                if message == Message::Stop {
+                    stopped.store(true, Ordering::SeqCst);
+                    break;
+                }
+            }
+            cancel.cancel();
+        })
+    };
+
+    // Tras el Stop el coordinador puede haber cerrado ya: ese error no cuenta
+    let mut outcome = Ok(());
+    let fail = |e: VanityError, outcome: &mut Result<()>| {
+        if outcome.is_ok() && !stopped.load(Ordering::SeqCst) {
+            *outcome = Err(e);
+        }
+        cancel.cancel();
+    };
+    let mut reported = 0;
+    let search = std::thread::scope(|scope| {
+        let search = scope.spawn(|| {
+            search_vanity(&config, |result| {
+                let _ = updates_tx.send(WorkerUpdate::Found(result));
+                Ok(())
+            })
+        });
+
+        loop {
+            if !running.load(Ordering::Relaxed) {
+                cancel.cancel();
+            }
+            let finished = search.is_finished();
+            let update = match updates.recv_timeout(Duration::from_millis(100)) {
+                Ok(update) => update,
+                Err(_) if finished => break,
+                Err(_) => continue,
+            };
+
+            match update {
+                WorkerUpdate::Found(result) => {
+                    let wire = WireResult::from(&result);
+                    let found = Message::Found {
+                        result: wire.clone(),
+                    };
+                    if outcome.is_err() {
+                        pending.push(wire);
+                    } else if let Err(e) = outgoing.send(&mut writer, &found) {
+                        pending.push(wire);
+                        fail(e, &mut outcome);
+                    } else {
+                        stats.results_found += 1;
+                        on_event(WorkerEvent::Found {
+                            address: result.address,
+                        });
+                    }
+                }
+                WorkerUpdate::Attempts(total) if outcome.is_ok() => {
+                    let attempts = total - reported;
+                    match outgoing.send(&mut writer, &Message::Progress { attempts }) {
+                        Ok(()) => reported = total,
+                        Err(e) => fail(e, &mut outcome),
+                    }
+                }
+                WorkerUpdate::Attempts(_) => {}
+            }
+        }
+        search
+            .join()
+            .expect("el hilo de búsqueda no debe entrar en pánico")
+    });
+
+    // Informe final con los intentos que aún no se habían enviado
+    match search {
+        Ok(search) => {
+            stats.total_attempts += search.total_attempts;
+            if outcome.is_ok() {
+                let attempts = search.total_attempts - reported;
+                if let Err(e) = outgoing.send(&mut writer, &Message::Progress { attempts }) {
+                    fail(e, &mut outcome);
+                }
+            }
+        }
+        Err(e) => fail(e, &mut outcome),
+    }
+
+    let _ = writer.shutdown(Shutdown::Both);
+    let _ = reader_handle.join();
+
+    outcome?;
+    if stopped.load(Ordering::SeqCst) {
+        Ok(SessionEnd::Stopped)
+    } else if !running.load(Ordering::Relaxed) {
+        Ok(SessionEnd::Cancelled)
+    } else {
+        Err(std::io::Error::from(std::io::ErrorKind::ConnectionAborted).into())
+    }
+}
+
+/// Lo que la búsqueda de un worker entrega a su sesión
+enum WorkerUpdate {
+    /// Intentos totales de la búsqueda en el último informe
+    Attempts(u64),
+    /// Resultado encontrado
+    Found(VanityResult),
+}
+
+/// Observador que pasa los informes de progreso de la búsqueda a la sesión
+#[derive(Debug)]
+struct AttemptRelay(Sender<WorkerUpdate>);
+
+impl SearchObserver for AttemptRelay {
+    fn on_progress(&self, report: &ProgressReport) {
+        let _ = self
+            .0
+            .send(WorkerUpdate::Attempts(report.stats.total_attempts));
+    }
+}
+
+/// Handshake del lado del worker
+///
+/// # Returns
+///
+/// El trabajo (None si ya terminó) y los dos sentidos de la conexión:
+/// mensajes del coordinador y mensajes propios
+fn worker_handshake(
+    options: &WorkerOptions,
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+) -> Result<(Option<Job>, Channel, Channel)> {
+    let worker_nonce = new_nonce();
+    send_message(
+        writer,
+        &Message::Hello {
+            version: PROTOCOL_VERSION,
+            worker: options.name.clone(),
+            nonce: worker_nonce.clone(),
+        },
+    )?;
+
+    let nonce = match expect_message(reader)? {
+        Message::Challenge {
+            version,
+            nonce,
+            mac,
+        } => {
+            if version != PROTOCOL_VERSION {
+                return Err(VanityError::InvalidConfig(format!(
+                    "El coordinador usa la versión de protocolo {} (este worker, la {})",
+                    version, PROTOCOL_VERSION
+                )));
+            }
+            let parts: [&[u8]; 3] = [COORDINATOR, worker_nonce.as_bytes(), nonce.as_bytes()];
+            if !verify(&options.key, &parts, &mac) {
+                return Err(VanityError::InvalidConfig(
+                    "El coordinador no conoce la clave compartida".to_string(),
+                ));
+            }
+            nonce
+        }
+        other => return Err(unexpected(other)),
+    };
+
+    let parts: [&[u8]; 4] = [
+        WORKER,
+        nonce.as_bytes(),
+        worker_nonce.as_bytes(),
+        options.name.as_bytes(),
+    ];
+    send_message(
+        writer,
+        &Message::Auth {
+            mac: sign(&options.key, &parts),
+        },
+    )?;
+
+    // A partir de aquí todo va sellado; un rechazo aún llega en claro
+    let keys = SessionKeys::derive(&options.key, &worker_nonce, &nonce, &options.name);
+    let mut incoming = Channel::new(&keys, COORDINATOR);
+    let outgoing = Channel::new(&keys, WORKER);
+    let mut line = String::new();
+    reader.take(MAX_MESSAGE_BYTES).read_line(&mut line)?;
+    if let Ok(rejected @ Message::Rejected { .. }) = serde_json::from_str::<Message>(&line) {
+        return Err(unexpected(rejected));
+    }
+    let job = match incoming.receive(&mut line.as_bytes())? {
+        Some(Message::Job { job }) => Some(job),
+        Some(Message::Stop) => None,
+        Some(other) => return Err(unexpected(other)),
+        None => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+    };
+    Ok((job, incoming, outgoing))
+}
+
+/// Lee un mensaje que debe existir (la conexión no puede estar cerrada)
+fn expect_message(reader: &mut impl BufRead) -> Result<Message> {
+    receive_message(reader)?
+        .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into())
+}
+
+/// Error para un mensaje fuera de lugar durante el handshake
+fn unexpected(message: Message) -> VanityError {
+    match message {
+        Message::Rejected { reason } => {
+            VanityError::InvalidConfig(format!("El coordinador rechazó la conexión: {}", reason))
+        }
+        other => {
+            VanityError::InvalidConfig(format!("Mensaje inesperado del coordinador: {:?}", other))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"clave-de-prueba-compartida";
+
+    fn job(max_results: usize) -> Job {
+        Job {
+            prefixes: vec!["a".to_string()],
+            suffixes: Vec::new(),
+            regexes: Vec::new(),
+            max_results,
+            max_attempts: None,
+            deadline: None,
+        }
+    }
+
+    fn worker(coordinator: SocketAddr, name: &str, key: &[u8]) -> WorkerOptions {
+        WorkerOptions {
+            coordinator: coordinator.to_string(),
+            key: key.to_vec(),
+            name: name.to_string(),
+            threads: 1,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mac = sign(KEY, &[b"worker", b"abc"]);
+        assert!(verify(KEY, &[b"worker", b"abc"], &mac));
+        assert!(!verify(KEY, &[b"worker", b"abd"], &mac));
+        assert!(!verify(KEY, &[b"workerabc"], &mac)); // Las partes no se confunden
+        assert!(!verify(b"otra-clave-distinta", &[b"worker", b"abc"], &mac));
+        assert!(!verify(KEY, &[b"worker", b"abc"], "no-es-hex"));
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        let message = Message::Job { job: job(3) };
+        let mut buffer = Vec::new();
+        send_message(&mut buffer, &message).unwrap();
+        assert!(buffer.ends_with(b"\n"));
+
+        let mut reader = &buffer[..];
+        assert_eq!(receive_message(&mut reader).unwrap(), Some(message));
+        assert_eq!(receive_message::<Message>(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_session_channel() {
+        let keys = SessionKeys::derive(KEY, "nonce-worker", "nonce-coordinador", "w1");
+        let mut sender = Channel::new(&keys, WORKER);
+        let mut buffer = Vec::new();
+        sender
+            .send(&mut buffer, &Message::Progress { attempts: 42 })
+            .unwrap();
+        sender.send(&mut buffer, &Message::Stop).unwrap();
+
+        // El contenido no viaja en claro
+        let first_line = buffer.split(|&b| b == b'\n').next().unwrap().to_vec();
+        assert!(!String::from_utf8_lossy(&first_line).contains("progress"));
+
+        let mut receiver = Channel::new(&keys, WORKER);
+        let mut reader = &buffer[..];
+        assert_eq!(
+            receiver.receive(&mut reader).unwrap(),
+            Some(Message::Progress { attempts: 42 })
+        );
+        assert_eq!(receiver.receive(&mut reader).unwrap(), Some(Message::Stop));
+        assert_eq!(receiver.receive(&mut reader).unwrap(), None);
+
+        // Un mensaje repetido o desordenado se rechaza
+        let mut replay = first_line.clone();
+        replay.push(b'\n');
+        assert!(receiver.receive(&mut &replay[..]).is_err());
+
+        // Igual que uno alterado, uno del otro sentido o uno de otra conexión
+        let mut sealed: SealedMessage = serde_json::from_slice(&first_line).unwrap();
+        sealed.payload.replace_range(
+            ..2,
+            if sealed.payload.starts_with("00") {
+                "01"
+            } else {
+                "00"
+            },
+        );
+        let mut tampered = Vec::new();
+        send_message(&mut tampered, &sealed).unwrap();
+        assert!(Channel::new(&keys, WORKER)
+            .receive(&mut &tampered[..])
+            .is_err());
+        assert!(Channel::new(&keys, COORDINATOR)
+            .receive(&mut &buffer[..])
+            .is_err());
+        let other = SessionKeys::derive(KEY, "otro-nonce", "nonce-coordinador", "w1");
+        assert!(Channel::new(&other, WORKER)
+            .receive(&mut &buffer[..])
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_result() {
+        let seed = [3u8; 32];
+        let secret = crate::generator::expand_secret_key(&seed);
+        let address = Candidate::new(&public_key_from_secret(&secret)).into_address();
+        let wire = WireResult {
+            private_key: hex::encode(secret),
+            found_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let first = &address[..1];
+        let matching = PrefixMatcher::new(&[first.to_string()]).unwrap();
+        let result = verify_result(&wire, &matching).unwrap();
+        assert_eq!(result.address, address);
+        assert_eq!(result.matched_prefix, first);
+
+        // Un resultado que no cumple el patrón se rechaza
+        let other = if first == "a" { "b" } else { "a" };
+        let other = PrefixMatcher::new(&[other.to_string()]).unwrap();
+        assert!(verify_result(&wire, &other).is_err());
+
+        let broken = WireResult {
+            private_key: "00".to_string(),
+            ..wire
+        };
+        assert!(verify_result(&broken, &matching).is_err());
+    }
+
+    #[test]
+    fn test_distributed_search() {
+        let coordinator = Coordinator::bind("127.0.0.1:0", KEY, job(3)).unwrap();
+        let addr = coordinator.local_addr().unwrap();
+
+        let workers: Vec<_> = ["w1", "w2"]
+            .into_iter()
+            .map(|name| {
+                let options = worker(addr, name, KEY);
+                std::thread::spawn(move || run_worker(&options, &AtomicBool::new(true), |_| {}))
+            })
+            .collect();
+
+        let mut found = Vec::new();
+        let stats = coordinator
+            .run(&AtomicBool::new(true), |event| {
+                if let CoordinatorEvent::Found { result, .. } = event {
+                    assert!(result.address.starts_with('a'));
+                    found.push(result);
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(found.len(), 3);
+        assert_eq!(stats.results_found, 3);
+
+        // Todos los workers reciben Stop y terminan
+        for handle in workers {
+            assert!(handle.join().unwrap().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_worker_progress() {
+        // Sin resultados a la vista: el coordinador para al sumar los
+        // intentos que informa la búsqueda del worker
+        let mut job = job(1);
+        job.prefixes = vec!["zzzzzz".to_string()];
+        job.max_attempts = Some(20_000);
+        let coordinator = Coordinator::bind("127.0.0.1:0", KEY, job).unwrap();
+        let addr = coordinator.local_addr().unwrap();
+
+        let options = worker(addr, "w1", KEY);
+        let handle =
+            std::thread::spawn(move || run_worker(&options, &AtomicBool::new(true), |_| {}));
+
+        let stats = coordinator.run(&AtomicBool::new(true), |_| Ok(())).unwrap();
+        assert!(stats.total_attempts >= 20_000);
+        assert_eq!(stats.results_found, 0);
+
+        let worker_stats = handle.join().unwrap().unwrap();
+        assert!(worker_stats.total_attempts >= stats.total_attempts);
+    }
+
+    #[test]
+    fn test_coordinator_deadline() {
+        let mut job = job(1);
+        job.prefixes = vec!["zzzzzz".to_string()];
+        job.deadline = Some(Instant::now() + Duration::from_secs(1));
+        let coordinator = Coordinator::bind("127.0.0.1:0", KEY, job).unwrap();
+        let addr = coordinator.local_addr().unwrap();
+
+        let options = worker(addr, "w1", KEY);
+        let handle =
+            std::thread::spawn(move || run_worker(&options, &AtomicBool::new(true), |_| {}));
+
+        // Al pasar el deadline se detiene a los workers sin resultados
+        let stats = coordinator.run(&AtomicBool::new(true), |_| Ok(())).unwrap();
+        assert_eq!(stats.results_found, 0);
+        assert!(stats.elapsed_seconds < 10.0);
+        assert!(handle.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let coordinator = Coordinator::bind("127.0.0.1:0", KEY, job(1))
+            .unwrap()
+            .with_max_connections(1);
+        let addr = coordinator.local_addr().unwrap();
+        let running = Arc::new(AtomicBool::new(true));
+        let (rejected_tx, rejected) = unbounded();
+        let handle = {
+            let running = Arc::clone(&running);
+            std::thread::spawn(move || {
+                coordinator.run(&running, |event| {
+                    if let CoordinatorEvent::Rejected { reason, .. } = event {
+                        let _ = rejected_tx.send(reason);
+                    }
+                    Ok(())
+                })
+            })
+        };
+
+        // Una conexión que no hace el handshake ocupa el único hueco
+        let _idle = TcpStream::connect(addr).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        let _extra = TcpStream::connect(addr).unwrap();
+
+        let reason = rejected.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(reason.contains("demasiadas conexiones"));
+
+        running.store(false, Ordering::Relaxed);
+        assert!(matches!(
+            handle.join().unwrap(),
+            Err(VanityError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_result() {
+        let seed = [3u8; 32];
+        let secret = crate::generator::expand_secret_key(&seed);
+        let address = Candidate::new(&public_key_from_secret(&secret)).into_address();
+        let wire = WireResult {
+            private_key: hex::encode(secret),
+            found_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let mut job = job(2);
+        job.prefixes = vec![address[..1].to_string()];
+        let coordinator = Coordinator::bind("127.0.0.1:0", KEY, job).unwrap();
+        let addr = coordinator.local_addr().unwrap();
+        let running = Arc::new(AtomicBool::new(true));
+        let (events_tx, events) = unbounded();
+        let handle = {
+            let running = Arc::clone(&running);
+            std::thread::spawn(move || {
+                coordinator.run(&running, |event| {
+                    match event {
+                        CoordinatorEvent::Found { .. } => {
+                            let _ = events_tx.send(None);
+                        }
+                        CoordinatorEvent::InvalidResult { reason, .. } => {
+                            let _ = events_tx.send(Some(reason));
+                        }
+                        _ => {}
+                    }
+                    Ok(())
+                })
+            })
+        };
+
+        // Un worker autenticado que envía dos veces el mismo resultado
+        let options = worker(addr, "w1", KEY);
+        let mut writer = TcpStream::connect(addr).unwrap();
+        let mut reader = BufReader::new(writer.try_clone().unwrap());
+        let (job, _incoming, mut outgoing) =
+            worker_handshake(&options, &mut reader, &mut writer).unwrap();
+        assert!(job.is_some());
+        for _ in 0..2 {
+            let found = Message::Found {
+                result: wire.clone(),
+            };
+            outgoing.send(&mut writer, &found).unwrap();
+        }
+
+        // Solo el primero cuenta; el segundo se rechaza y la búsqueda sigue
+        assert_eq!(events.recv_timeout(Duration::from_secs(5)).unwrap(), None);
+        let reason = events
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert!(reason.contains("ya se había recibido"));
+
+        running.store(false, Ordering::Relaxed);
+        assert!(matches!(
+            handle.join().unwrap(),
+            Err(VanityError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_worker_with_wrong_key() {
+        let coordinator = Coordinator::bind("127.0.0.1:0", KEY, job(1)).unwrap();
+        let addr = coordinator.local_addr().unwrap();
+        let handle = std::thread::spawn(move || {
+            let mut rejected = 0;
+            coordinator
+                .run(&AtomicBool::new(true), |event| {
+                    if let CoordinatorEvent::Rejected { .. } = event {
+                        rejected += 1;
+                    }
+                    Ok(())
+                })
+                .map(|_| rejected)
+        });
+
+        // La clave incorrecta se detecta ya en el reto del coordinador
+        let intruder = worker(addr, "intruso", b"clave-incorrecta-de-prueba");
+        let outcome = run_worker(&intruder, &AtomicBool::new(true), |_| {});
+        assert!(matches!(outcome, Err(VanityError::InvalidConfig(_))));
+
+        let honest = worker(addr, "w1", KEY);
+        assert!(run_worker(&honest, &AtomicBool::new(true), |_| {}).is_ok());
+        assert!(handle.join().unwrap().unwrap() >= 1);
+    }
+
+    #[test]
+    fn test_load_cluster_key() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("cluster.key");
+
+        std::fs::write(&path, "  clave-suficientemente-larga\n").unwrap();
+        assert_eq!(
+            load_cluster_key(&path).unwrap(),
+            b"clave-suficientemente-larga"
+        );
+
+        std::fs::write(&path, "corta").unwrap();
+        assert!(load_cluster_key(&path).is_err());
+    }
+}
@@ -0,0 +1,363 @@
+//! Aritmética mínima sobre GF(2^255 - 19) para el recorrido de claves
+//!
+//! curve25519-dalek no expone las coordenadas de sus puntos, así que la
+//! compresión en lote de `KeyWalker::next_batch` (una sola inversión de campo
+//! por lote) necesita su propia representación. Solo se implementa lo que usa
+//! el recorrido: suma mixta de puntos, inversión, descompresión y compresión.
+//!
+//! Los elementos se guardan en base 2^51 (cinco limbs de 51 bits), como el
+//! backend de 64 bits de dalek. Nada de esto es de tiempo constante: solo se
+//! procesan claves públicas; los escalares secretos nunca pasan por aquí.
+
+/// Máscara de 51 bits de un limb
+const LOW_51_BITS: u64 = (1 << 51) - 1;
+
+/// Exponente p - 2 (inversión por el pequeño teorema de Fermat), little-endian
+const P_MINUS_2: [u8; 32] = exponent(0xeb, 0x7f);
+
+/// Exponente (p - 5) / 8 = 2^252 - 3 (raíz cuadrada), little-endian
+const P_MINUS_5_DIV_8: [u8; 32] = exponent(0xfd, 0x0f);
+
+/// Exponente (p - 1) / 4 = 2^253 - 5; 2 elevado a él es sqrt(-1)
+const P_MINUS_1_DIV_4: [u8; 32] = exponent(0xfb, 0x1f);
+
+/// Exponente de la forma `first || 0xff * 30 || last` en little-endian
+const fn exponent(first: u8, last: u8) -> [u8; 32] {
+    let mut bytes = [0xffu8; 32];
+    bytes[0] = first;
+    bytes[31] = last;
+    bytes
+}
+
+/// Elemento de GF(2^255 - 19) en base 2^51
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    pub(crate) const ZERO: Self = Self([0; 5]);
+    pub(crate) const ONE: Self = Self([1, 0, 0, 0, 0]);
+
+    /// Elemento a partir de un entero pequeño
+    pub(crate) fn from_u64(value: u64) -> Self {
+        Self([value & LOW_51_BITS, value >> 51, 0, 0, 0]).reduce()
+    }
+
+    /// Carga 32 bytes little-endian ignorando el bit más alto
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load = |i: usize| {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[i..i + 8]);
+            u64::from_le_bytes(word)
+        };
+        Self([
+            load(0) & LOW_51_BITS,
+            (load(6) >> 3) & LOW_51_BITS,
+            (load(12) >> 6) & LOW_51_BITS,
+            (load(19) >> 1) & LOW_51_BITS,
+            (load(24) >> 12) & LOW_51_BITS,
+        ])
+    }
+
+    /// Codificación canónica en 32 bytes little-endian
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = self.reduce().0;
+
+        // Tras `reduce` el valor es < 2p: restar p si hace falta, calculando
+        // antes si el valor + 19 desborda 2^255
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LOW_51_BITS;
+        }
+        limbs[4] &= LOW_51_BITS;
+
+        let mut bytes = [0u8; 32];
+        let mut accumulator: u128 = 0;
+        let mut bits = 0;
+        let mut index = 0;
+        for limb in limbs {
+            accumulator |= (limb as u128) << bits;
+            bits += 51;
+            while bits >= 8 {
+                bytes[index] = accumulator as u8;
+                accumulator >>= 8;
+                bits -= 8;
+                index += 1;
+            }
+        }
+        bytes[index] = accumulator as u8;
+        bytes
+    }
+
+    /// Propaga los acarreos para dejar cada limb en 51 bits (más un acarreo)
+    fn reduce(self) -> Self {
+        let mut limbs = self.0;
+        let carries: [u64; 5] = std::array::from_fn(|i| limbs[i] >> 51);
+        for limb in limbs.iter_mut() {
+            *limb &= LOW_51_BITS;
+        }
+        limbs[0] += carries[4] * 19;
+        for i in 0..4 {
+            limbs[i + 1] += carries[i];
+        }
+        Self(limbs)
+    }
+
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + other.0[i])).reduce()
+    }
+
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        // Sumar 16p antes de restar para que ningún limb quede negativo
+        const SIXTEEN_P: [u64; 5] = [
+            36028797018963664,
+            36028797018963952,
+            36028797018963952,
+            36028797018963952,
+            36028797018963952,
+        ];
+        Self(std::array::from_fn(|i| (self.0[i] + SIXTEEN_P[i]) - other.0[i])).reduce()
+    }
+
+    pub(crate) fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    pub(crate) fn mul(&self, other: &Self) -> Self {
+        let a = self.0.map(|limb| limb as u128);
+        let b = other.0.map(|limb| limb as u128);
+        let b19: [u128; 5] = std::array::from_fn(|i| b[i] * 19);
+
+        let c0 = a[0] * b[0] + a[4] * b19[1] + a[3] * b19[2] + a[2] * b19[3] + a[1] * b19[4];
+        let mut c1 = a[1] * b[0] + a[0] * b[1] + a[4] * b19[2] + a[3] * b19[3] + a[2] * b19[4];
+        let mut c2 = a[2] * b[0] + a[1] * b[1] + a[0] * b[2] + a[4] * b19[3] + a[3] * b19[4];
+        let mut c3 = a[3] * b[0] + a[2] * b[1] + a[1] * b[2] + a[0] * b[3] + a[4] * b19[4];
+        let mut c4 = a[4] * b[0] + a[3] * b[1] + a[2] * b[2] + a[1] * b[3] + a[0] * b[4];
+
+        c1 += c0 >> 51;
+        c2 += c1 >> 51;
+        c3 += c2 >> 51;
+        c4 += c3 >> 51;
+
+        let mut limbs = [
+            c0 as u64 & LOW_51_BITS,
+            c1 as u64 & LOW_51_BITS,
+            c2 as u64 & LOW_51_BITS,
+            c3 as u64 & LOW_51_BITS,
+            c4 as u64 & LOW_51_BITS,
+        ];
+        limbs[0] += (c4 >> 51) as u64 * 19;
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= LOW_51_BITS;
+        Self(limbs)
+    }
+
+    pub(crate) fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// Potencia con exponente de 256 bits en little-endian
+    fn pow(&self, exponent: &[u8; 32]) -> Self {
+        let mut result = Self::ONE;
+        for byte in exponent.iter().rev() {
+            for bit in (0..8).rev() {
+                result = result.square();
+                if (byte >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+
+    /// Inverso multiplicativo (0 para 0)
+    pub(crate) fn invert(&self) -> Self {
+        self.pow(&P_MINUS_2)
+    }
+
+    /// Bit de signo de la codificación de Ed25519 (paridad del valor canónico)
+    pub(crate) fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    pub(crate) fn ct_eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+/// Punto de Ed25519 en coordenadas extendidas (X:Y:Z:T), con x = X/Z,
+/// y = Y/Z y x·y = T/Z
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExtendedPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+/// Punto afín preparado para sumas mixtas: (y + x, y - x, 2d·x·y)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AffineNielsPoint {
+    y_plus_x: FieldElement,
+    y_minus_x: FieldElement,
+    xy2d: FieldElement,
+}
+
+/// Constante d de la curva: -121665 / 121666
+fn edwards_d() -> FieldElement {
+    FieldElement::from_u64(121665)
+        .neg()
+        .mul(&FieldElement::from_u64(121666).invert())
+}
+
+impl ExtendedPoint {
+    /// Descomprime una clave pública de 32 bytes
+    ///
+    /// # Returns
+    ///
+    /// None si los bytes no codifican un punto de la curva
+    pub(crate) fn decompress(bytes: &[u8; 32]) -> Option<Self> {
+        let y = FieldElement::from_bytes(bytes);
+        let sign = bytes[31] >> 7 == 1;
+
+        // x² = (y² - 1) / (d·y² + 1)
+        let y2 = y.square();
+        let u = y2.sub(&FieldElement::ONE);
+        let v = edwards_d().mul(&y2).add(&FieldElement::ONE);
+
+        // Candidato x = u·v³·(u·v⁷)^((p-5)/8)
+        let v3 = v.square().mul(&v);
+        let v7 = v3.square().mul(&v);
+        let mut x = u.mul(&v3).mul(&u.mul(&v7).pow(&P_MINUS_5_DIV_8));
+
+        let check = v.mul(&x.square());
+        if !check.ct_eq(&u) {
+            if !check.ct_eq(&u.neg()) {
+                return None;
+            }
+            let sqrt_m1 = FieldElement::from_u64(2).pow(&P_MINUS_1_DIV_4);
+            x = x.mul(&sqrt_m1);
+        }
+        if x.is_negative() != sign {
+            x = x.neg();
+        }
+
+        Some(Self {
+            x,
+            y,
+            z: FieldElement::ONE,
+            t: x.mul(&y),
+        })
+    }
+
+    /// Forma afín del punto para usarlo como sumando fijo
+    pub(crate) fn to_affine_niels(self) -> AffineNielsPoint {
+        let z_inv = self.z.invert();
+        let x = self.x.mul(&z_inv);
+        let y = self.y.mul(&z_inv);
+        AffineNielsPoint {
+            y_plus_x: y.add(&x),
+            y_minus_x: y.sub(&x),
+            xy2d: x.mul(&y).mul(&edwards_d().add(&edwards_d())),
+        }
+    }
+
+    /// Suma mixta con un punto afín (7 multiplicaciones)
+    pub(crate) fn add_affine(&self, other: &AffineNielsPoint) -> Self {
+        let pp = self.y.add(&self.x).mul(&other.y_plus_x);
+        let mm = self.y.sub(&self.x).mul(&other.y_minus_x);
+        let txy2d = self.t.mul(&other.xy2d);
+        let z2 = self.z.add(&self.z);
+
+        // Punto "completado" ((E:G), (H:F)) pasado a coordenadas extendidas
+        let e = pp.sub(&mm);
+        let h = pp.add(&mm);
+        let g = z2.add(&txy2d);
+        let f = z2.sub(&txy2d);
+        Self {
+            x: e.mul(&f),
+            y: h.mul(&g),
+            z: g.mul(&f),
+            t: e.mul(&h),
+        }
+    }
+
+    /// Comprime varios puntos con una sola inversión de campo
+    ///
+    /// Truco de Montgomery: se invierte el producto de todas las Z y de él se
+    /// recuperan las inversas individuales con tres multiplicaciones por punto.
+    pub(crate) fn compress_batch<const N: usize>(points: &[Self; N]) -> [[u8; 32]; N] {
+        let mut products = [FieldElement::ONE; N];
+        let mut accumulator = FieldElement::ONE;
+        for (product, point) in products.iter_mut().zip(points) {
+            *product = accumulator;
+            accumulator = accumulator.mul(&point.z);
+        }
+
+        let mut inverse = accumulator.invert();
+        let mut compressed = [[0u8; 32]; N];
+        for i in (0..N).rev() {
+            let z_inv = inverse.mul(&products[i]);
+            inverse = inverse.mul(&points[i].z);
+
+            let x = points[i].x.mul(&z_inv);
+            let mut bytes = points[i].y.mul(&z_inv).to_bytes();
+            bytes[31] ^= (x.is_negative() as u8) << 7;
+            compressed[i] = bytes;
+        }
+        compressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+    use curve25519_dalek::scalar::Scalar;
+
+    #[test]
+    fn test_invert_and_encoding() {
+        let value = FieldElement::from_bytes(&[0x5a; 32]);
+        let product = value.mul(&value.invert());
+        assert_eq!(product.to_bytes(), FieldElement::ONE.to_bytes());
+
+        // p - 1 codifica -1 en forma canónica
+        let mut minus_one = [0xffu8; 32];
+        minus_one[0] = 0xec;
+        minus_one[31] = 0x7f;
+        assert_eq!(FieldElement::ONE.neg().to_bytes(), minus_one);
+    }
+
+    #[test]
+    fn test_points_match_dalek() {
+        let base = ED25519_BASEPOINT_POINT;
+        let start = base * Scalar::from(12345u64);
+        let stride = base.mul_by_cofactor();
+
+        let mut point = ExtendedPoint::decompress(&start.compress().to_bytes()).unwrap();
+        let step = ExtendedPoint::decompress(&stride.compress().to_bytes())
+            .unwrap()
+            .to_affine_niels();
+
+        let mut ours = [point; 4];
+        let mut expected = start;
+        for (i, slot) in ours.iter_mut().enumerate() {
+            *slot = point;
+            assert_eq!(
+                ExtendedPoint::compress_batch(&[point])[0],
+                expected.compress().to_bytes(),
+                "paso {}",
+                i
+            );
+            point = point.add_affine(&step);
+            expected += stride;
+        }
+
+        let batch = ExtendedPoint::compress_batch(&ours);
+        assert_eq!(batch[3], (start + stride * Scalar::from(3u64)).compress().to_bytes());
+    }
+}
@@ -1,6 +1,11 @@
+use crate::field::{AffineNielsPoint, ExtendedPoint};
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::edwards::EdwardsPoint;
+use curve25519_dalek::scalar::{clamp_integer, Scalar};
 use data_encoding::BASE32_NOPAD;
-use ed25519_dalek::SigningKey;
 use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha512;
 use sha3::{Digest, Sha3_256};
 
 /// Versión del protocolo onion v3
@@ -12,22 +17,217 @@ const CHECKSUM_PREFIX: &[u8] = b".onion checksum";
 /// Sufijo para el cálculo del checksum
 const CHECKSUM_SUFFIX: &[u8] = b"\x03";
 
-/// Genera un par de claves Ed25519 aleatorio
-/// 
-/// Utiliza OsRng que es un generador de números aleatorios
-/// criptográficamente seguro proporcionado por el sistema operativo.
-/// 
+/// Alfabeto base32 de las direcciones .onion (RFC 4648 en minúsculas)
+pub const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Número de caracteres base32 de una dirección v3 (35 bytes * 8 / 5)
+pub const ONION_BODY_CHARS: usize = 56;
+
+/// Número de caracteres de la dirección determinados solo por la clave pública
+/// (256 bits / 5 bits por carácter); el carácter 52 ya incluye bits del checksum
+pub const PUBKEY_PREFIX_CHARS: usize = 51;
+
+/// Expande una semilla Ed25519 de 32 bytes a la clave secreta de 64 bytes
+///
+/// Igual que Ed25519: SHA-512 de la semilla, con el escalar (primera mitad)
+/// "clamped" y el prefijo de nonce (segunda mitad) sin modificar. Es el
+/// formato que Tor guarda en `hs_ed25519_secret_key`.
+///
+/// # Arguments
+///
+/// * `seed` - Semilla de 32 bytes
+///
 /// # Returns
-/// 
-/// Tupla con (clave_privada, clave_pública) como arrays de 32 bytes
-pub fn generate_keypair() -> ([u8; 32], [u8; 32]) {
-    let signing_key = SigningKey::generate(&mut OsRng);
-    let verifying_key = signing_key.verifying_key();
-    
-    let private_key = signing_key.to_bytes();
-    let public_key = verifying_key.to_bytes();
-    
-    (private_key, public_key)
+///
+/// Clave secreta expandida (escalar || prefijo de nonce)
+pub fn expand_secret_key(seed: &[u8; 32]) -> [u8; 64] {
+    let hash = Sha512::digest(seed);
+
+    let mut scalar = [0u8; 32];
+    scalar.copy_from_slice(&hash[..32]);
+
+    let mut expanded = [0u8; 64];
+    expanded[..32].copy_from_slice(&clamp_integer(scalar));
+    expanded[32..].copy_from_slice(&hash[32..]);
+    expanded
+}
+
+/// Clave pública Ed25519 de una clave secreta expandida
+///
+/// # Arguments
+///
+/// * `secret` - Clave secreta expandida (escalar || prefijo de nonce)
+///
+/// # Returns
+///
+/// La clave pública comprimida (32 bytes)
+pub fn public_key_from_secret(secret: &[u8; 64]) -> [u8; 32] {
+    let mut scalar = [0u8; 32];
+    scalar.copy_from_slice(&secret[..32]);
+    EdwardsPoint::mul_base_clamped(scalar).compress().to_bytes()
+}
+
+/// Suma `8 * steps` a un escalar de 32 bytes en little-endian
+fn add_scalar_steps(scalar: &[u8; 32], steps: u64) -> [u8; 32] {
+    let mut result = *scalar;
+    let mut carry = (steps as u128) * (KeyWalker::SCALAR_STRIDE as u128);
+    for byte in result.iter_mut() {
+        if carry == 0 {
+            break;
+        }
+        let sum = *byte as u128 + (carry & 0xff);
+        *byte = sum as u8;
+        carry = (carry >> 8) + (sum >> 8);
+    }
+    result
+}
+
+/// Número de candidatos que se procesan por lote en `KeyWalker::next_batch`
+///
+/// Todo el lote comparte una única inversión de campo al pasar a coordenadas
+/// afines. Lotes más grandes amortizan mejor la inversión pero ocupan más pila
+/// (unos 160 bytes por punto).
+pub const BATCH_SIZE: usize = 256;
+
+/// Lote de claves públicas consecutivas generado por un `KeyWalker`
+pub struct KeyBatch {
+    /// Paso del recorrido correspondiente a `public_keys[0]`
+    pub first_step: u64,
+    /// Claves públicas comprimidas, en orden de recorrido
+    pub public_keys: [[u8; 32]; BATCH_SIZE],
+}
+
+/// Recorrido incremental del espacio de claves Ed25519
+///
+/// En lugar de generar un par de claves nuevo por intento (OsRng + SHA-512 +
+/// multiplicación completa por el punto base), cada worker parte de un escalar
+/// aleatorio y avanza sumando un punto fijo: si el escalar crece en 8, la clave
+/// pública crece en 8·B. Cada candidato cuesta así una sola suma de puntos.
+///
+/// Se avanza de 8 en 8 (el cofactor) para que el escalar siga siendo un entero
+/// "clamped" válido: los 3 bits bajos quedan a cero y el bit 254 activo, de modo
+/// que las implementaciones que aplican clamping al cargar la clave (Tor, Arti,
+/// ed25519-dalek) obtienen exactamente la misma clave pública.
+pub struct KeyWalker {
+    /// Escalar inicial (clamped, little-endian)
+    base_scalar: [u8; 32],
+    /// Prefijo de nonce de la clave expandida
+    nonce_prefix: [u8; 32],
+    /// Pasos dados desde el escalar inicial
+    steps: u64,
+    /// Punto actual: (base_scalar + 8 * steps) · B
+    point: ExtendedPoint,
+    /// Incremento por paso: 8 · B
+    stride: AffineNielsPoint,
+}
+
+impl KeyWalker {
+    /// Incremento del escalar en cada paso
+    pub const SCALAR_STRIDE: u64 = 8;
+
+    /// Crea un recorrido desde una semilla aleatoria de OsRng
+    pub fn random() -> Self {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        Self::from_seed(&seed)
+    }
+
+    /// Crea un recorrido desde una semilla de 32 bytes
+    ///
+    /// El primer candidato es exactamente la clave Ed25519 estándar de la semilla.
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self::from_seed_at(seed, 0)
+    }
+
+    /// Crea un recorrido desde una semilla, situado ya en el paso `step`
+    ///
+    /// Permite continuar un recorrido determinista guardado en un checkpoint.
+    pub fn from_seed_at(seed: &[u8; 32], step: u64) -> Self {
+        let expanded = expand_secret_key(seed);
+
+        let mut base_scalar = [0u8; 32];
+        base_scalar.copy_from_slice(&expanded[..32]);
+        let mut nonce_prefix = [0u8; 32];
+        nonce_prefix.copy_from_slice(&expanded[32..]);
+
+        Self {
+            base_scalar,
+            nonce_prefix,
+            steps: step,
+            point: walker_point(&EdwardsPoint::mul_base_clamped(add_scalar_steps(&base_scalar, step))),
+            stride: walker_stride(),
+        }
+    }
+
+    /// Crea un recorrido de desplazamientos sobre un punto ajeno
+    ///
+    /// Para la generación con clave dividida: los candidatos son
+    /// `base + (offset + 8 * paso) · B` y `secret_key_at` devuelve solo el
+    /// desplazamiento (con prefijo de nonce a cero), nunca una clave completa.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Punto público del solicitante
+    /// * `offset` - Desplazamiento inicial (múltiplo de 8, little-endian)
+    pub fn from_offset(base: &EdwardsPoint, offset: [u8; 32]) -> Self {
+        Self {
+            base_scalar: offset,
+            nonce_prefix: [0u8; 32],
+            steps: 0,
+            point: walker_point(&(base + EdwardsPoint::mul_base(&Scalar::from_bytes_mod_order(offset)))),
+            stride: walker_stride(),
+        }
+    }
+
+    /// Pasos dados desde el escalar inicial (posición del próximo candidato)
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Calcula las claves públicas de los próximos `BATCH_SIZE` candidatos
+    ///
+    /// Los puntos se acumulan en coordenadas proyectivas (una suma por
+    /// candidato) y se comprimen juntos con el truco de inversión simultánea
+    /// de Montgomery: una sola inversión de campo para todo el lote.
+    pub fn next_batch(&mut self) -> KeyBatch {
+        let first_step = self.steps;
+
+        let points: [ExtendedPoint; BATCH_SIZE] = std::array::from_fn(|_| {
+            let point = self.point;
+            self.point = self.point.add_affine(&self.stride);
+            point
+        });
+        self.steps += BATCH_SIZE as u64;
+
+        KeyBatch {
+            first_step,
+            public_keys: ExtendedPoint::compress_batch(&points),
+        }
+    }
+
+    /// Clave secreta expandida (64 bytes) del candidato en el paso `step`
+    pub fn secret_key_at(&self, step: u64) -> [u8; 64] {
+        let scalar = add_scalar_steps(&self.base_scalar, step);
+        // Múltiplo de 8 y sin desbordar; los recorridos de `from_seed` además
+        // conservan el bit 254 (clamped)
+        debug_assert!(scalar[0] & 7 == 0 && scalar[31] < 0x80);
+
+        let mut secret = [0u8; 64];
+        secret[..32].copy_from_slice(&scalar);
+        secret[32..].copy_from_slice(&self.nonce_prefix);
+        secret
+    }
+}
+
+/// Pasa un punto de dalek a la representación propia del recorrido
+fn walker_point(point: &EdwardsPoint) -> ExtendedPoint {
+    ExtendedPoint::decompress(&point.compress().to_bytes())
+        .expect("un punto comprimido por dalek siempre se descomprime")
+}
+
+/// Incremento fijo del recorrido (8 · B) en forma afín
+fn walker_stride() -> AffineNielsPoint {
+    walker_point(&ED25519_BASEPOINT_POINT.mul_by_cofactor()).to_affine_niels()
 }
 
 /// Calcula el checksum para una dirección .onion v3
@@ -48,7 +248,7 @@ pub fn calculate_checksum(public_key: &[u8; 32]) -> [u8; 2] {
     // Construir el mensaje: ".onion checksum" || PUBKEY || VERSION || "\x03"
     hasher.update(CHECKSUM_PREFIX);
     hasher.update(public_key);
-    hasher.update(&[ONION_V3_VERSION]);
+    hasher.update([ONION_V3_VERSION]);
     hasher.update(CHECKSUM_SUFFIX);
     
     let hash = hasher.finalize();
@@ -75,13 +275,7 @@ pub fn calculate_checksum(public_key: &[u8; 32]) -> [u8; 2] {
 /// 
 /// String con la dirección .onion completa (56 caracteres + ".onion")
 pub fn generate_onion_address(public_key: &[u8; 32]) -> String {
-    let checksum = calculate_checksum(public_key);
-    
-    // Construir el payload: PUBKEY (32) || CHECKSUM (2) || VERSION (1) = 35 bytes
-    let mut payload = Vec::with_capacity(35);
-    payload.extend_from_slice(public_key);
-    payload.extend_from_slice(&checksum);
-    payload.push(ONION_V3_VERSION);
+    let payload = onion_payload(public_key);
     
     // Codificar en base32 (sin padding) y convertir a minúsculas
     let encoded = BASE32_NOPAD.encode(&payload).to_lowercase();
@@ -90,29 +284,346 @@ pub fn generate_onion_address(public_key: &[u8; 32]) -> String {
     format!("{}.onion", encoded)
 }
 
-/// Verifica si una dirección .onion cumple con alguno de los prefijos dados
-/// 
-/// # Arguments
-/// 
-/// * `address` - Dirección .onion completa
-/// * `prefixes` - Lista de prefijos a buscar (en minúsculas)
-/// 
+/// Construye el payload binario de una dirección v3 sin codificarlo
+///
+/// PUBKEY (32) || CHECKSUM (2) || VERSION (1) = 35 bytes
+pub fn onion_payload(public_key: &[u8; 32]) -> [u8; 35] {
+    let checksum = calculate_checksum(public_key);
+
+    let mut payload = [0u8; 35];
+    payload[..32].copy_from_slice(public_key);
+    payload[32..34].copy_from_slice(&checksum);
+    payload[34] = ONION_V3_VERSION;
+    payload
+}
+
+/// Valor de 5 bits del carácter `index` de la dirección, leído del payload
+pub fn payload_symbol(payload: &[u8; 35], index: usize) -> u8 {
+    let bit = index * 5;
+    let byte = bit / 8;
+    let high = payload[byte] as u16;
+    let low = payload.get(byte + 1).copied().unwrap_or(0) as u16;
+    (((high << 8) | low) >> (11 - bit % 8)) as u8 & 0x1f
+}
+
+/// Bits del carácter `index` fijados por el formato v3, como (máscara, valor)
+///
+/// Los últimos caracteres de la dirección incluyen bits del byte de versión
+/// (0x03), que son constantes: el último carácter siempre es 'd' y el
+/// penúltimo solo puede ser 'a', 'i', 'q' o 'y'. El resto de bits (clave
+/// pública y checksum) se consideran uniformes.
+pub fn fixed_symbol_bits(index: usize) -> (u8, u8) {
+    let mut mask = 0u8;
+    let mut value = 0u8;
+
+    for bit in 0..5 {
+        let position = index * 5 + bit;
+        if position >= 34 * 8 {
+            let version_bit = (ONION_V3_VERSION >> (7 - (position - 34 * 8))) & 1;
+            mask |= 0x10 >> bit;
+            value |= version_bit << (4 - bit);
+        }
+    }
+
+    (mask, value)
+}
+
+/// Probabilidad de que una dirección aleatoria termine en `suffix`
+///
+/// Devuelve 0 si el sufijo es imposible por los bits fijos del formato v3 o
+/// si contiene caracteres fuera del alfabeto base32.
+pub fn suffix_probability(suffix: &str) -> f64 {
+    if suffix.len() > ONION_BODY_CHARS {
+        return 0.0;
+    }
+
+    let start = ONION_BODY_CHARS - suffix.len();
+    let mut probability = 1.0;
+
+    for (i, ch) in suffix.chars().enumerate() {
+        let Some(symbol) = base32_value(ch) else {
+            return 0.0;
+        };
+        let (mask, value) = fixed_symbol_bits(start + i);
+        if symbol & mask != value {
+            return 0.0;
+        }
+        probability /= (1u32 << (5 - mask.count_ones())) as f64;
+    }
+
+    probability
+}
+
+/// Explica por qué un sufijo no puede aparecer en una dirección v3
+///
 /// # Returns
-/// 
-/// Option con el prefijo que coincidió, o None si no hay coincidencia
-pub fn matches_prefix(address: &str, prefixes: &[String]) -> Option<String> {
-    // Remover el sufijo .onion para comparar solo la parte base32
-    let address_without_suffix = address.strip_suffix(".onion").unwrap_or(address);
-    
-    for prefix in prefixes {
-        if address_without_suffix.starts_with(prefix) {
-            return Some(prefix.clone());
+///
+/// Option con la explicación, o None si el sufijo es posible
+pub fn explain_impossible_suffix(suffix: &str) -> Option<String> {
+    if suffix.chars().any(|ch| base32_value(ch).is_none()) {
+        return Some("contiene caracteres inválidos. Solo se permiten a-z y 2-7".to_string());
+    }
+    if suffix.len() > ONION_BODY_CHARS {
+        return Some(format!("es más largo que la dirección ({} caracteres)", ONION_BODY_CHARS));
+    }
+
+    // Revisar desde el final: el último carácter es la restricción más fuerte
+    let start = ONION_BODY_CHARS - suffix.len();
+    let chars: Vec<char> = suffix.chars().collect();
+    for (i, &ch) in chars.iter().enumerate().rev() {
+        let index = start + i;
+        let (mask, value) = fixed_symbol_bits(index);
+        let symbol = base32_value(ch)?;
+        if symbol & mask == value {
+            continue;
         }
+
+        let allowed: Vec<String> = BASE32_ALPHABET
+            .iter()
+            .enumerate()
+            .filter(|(v, _)| *v as u8 & mask == value)
+            .map(|(_, c)| format!("'{}'", *c as char))
+            .collect();
+        let position = if index == ONION_BODY_CHARS - 1 {
+            "el último carácter".to_string()
+        } else {
+            format!("el carácter {} desde el final", ONION_BODY_CHARS - index)
+        };
+        return Some(format!(
+            "{} de una dirección v3 solo puede ser {} (byte de versión 0x03)",
+            position,
+            allowed.join(", ")
+        ));
     }
-    
+
     None
 }
 
+/// Valor de 5 bits de un carácter del alfabeto base32 (a-z, 2-7)
+pub fn base32_value(ch: char) -> Option<u8> {
+    BASE32_ALPHABET
+        .iter()
+        .position(|&c| c as char == ch)
+        .map(|v| v as u8)
+}
+
+/// Prefijo decodificado a una máscara de bits sobre la clave pública
+///
+/// El carácter `i` de la dirección corresponde a los bits `5i..5i+5` de la
+/// clave pública (big-endian dentro de cada byte), así que un prefijo se puede
+/// comprobar directamente contra los 32 bytes de la clave, sin construir la
+/// dirección ni reservar memoria.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixMask {
+    /// Prefijo original
+    prefix: String,
+    /// Bits esperados (solo donde `mask` vale 1)
+    value: [u8; 32],
+    /// Bits de la clave pública que fija el prefijo
+    mask: [u8; 32],
+    /// Número de bytes de la clave afectados por la máscara
+    bytes: usize,
+}
+
+impl PrefixMask {
+    /// Decodifica un prefijo base32 a su máscara de bits
+    ///
+    /// Los caracteres más allá de `PUBKEY_PREFIX_CHARS` dependen del checksum y
+    /// no se incluyen en la máscara: para esos prefijos `matches` es solo una
+    /// condición necesaria y el resultado debe confirmarse sobre la dirección.
+    pub fn new(prefix: &str) -> Result<Self, String> {
+        let mut value = [0u8; 32];
+        let mut mask = [0u8; 32];
+
+        for (i, ch) in prefix.chars().enumerate() {
+            let symbol = base32_value(ch).ok_or_else(|| {
+                format!(
+                    "Prefijo '{}' contiene caracteres inválidos. Solo se permiten a-z y 2-7",
+                    prefix
+                )
+            })?;
+
+            for bit in 0..5 {
+                let position = i * 5 + bit;
+                if position >= 256 {
+                    break;
+                }
+                let byte_bit = 0x80 >> (position % 8);
+                mask[position / 8] |= byte_bit;
+                if symbol & (0x10 >> bit) != 0 {
+                    value[position / 8] |= byte_bit;
+                }
+            }
+        }
+
+        let bits = (prefix.len() * 5).min(256);
+        Ok(Self {
+            prefix: prefix.to_string(),
+            value,
+            mask,
+            bytes: bits.div_ceil(8),
+        })
+    }
+
+    /// Decodifica una lista de prefijos
+    pub fn compile_all(prefixes: &[String]) -> Result<Vec<Self>, String> {
+        prefixes.iter().map(|prefix| Self::new(prefix)).collect()
+    }
+
+    /// Prefijo original
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Comprueba los bits de la clave pública contra la máscara
+    pub fn matches(&self, public_key: &[u8; 32]) -> bool {
+        public_key[..self.bytes]
+            .iter()
+            .zip(&self.mask[..self.bytes])
+            .zip(&self.value[..self.bytes])
+            .all(|((key, mask), value)| key & mask == *value)
+    }
+}
+
+/// Valor de 5 bits del carácter `index` leído directamente de la clave pública
+///
+/// Solo válido para `index < PUBKEY_PREFIX_CHARS`; el carácter 51 ya mezcla
+/// bits del checksum.
+fn key_symbol(public_key: &[u8; 32], index: usize) -> u8 {
+    let bit = index * 5;
+    let byte = bit / 8;
+    let high = public_key[byte] as u16;
+    let low = public_key.get(byte + 1).copied().unwrap_or(0) as u16;
+    (((high << 8) | low) >> (11 - bit % 8)) as u8 & 0x1f
+}
+
+/// Nodo del trie: un hijo por símbolo base32 (0 = sin hijo)
+#[derive(Debug, Clone)]
+struct TrieNode {
+    /// Índice del nodo hijo para cada uno de los 32 símbolos
+    children: [u32; 32],
+    /// Prefijo que termina en este nodo, si lo hay
+    terminal: Option<u32>,
+}
+
+impl TrieNode {
+    fn new() -> Self {
+        Self {
+            children: [0; 32],
+            terminal: None,
+        }
+    }
+}
+
+/// Trie de prefijos sobre los símbolos de 5 bits de la clave pública
+///
+/// Cada nivel consume un carácter leído de los bits de la clave, así que el
+/// coste de evaluar un candidato depende de la longitud del camino recorrido
+/// (casi siempre uno o dos niveles) y no del número de prefijos cargados.
+#[derive(Debug, Clone)]
+pub struct PrefixTrie {
+    /// Nodos; el 0 es la raíz
+    nodes: Vec<TrieNode>,
+    /// Prefijos insertados, indexados por `TrieNode::terminal`
+    prefixes: Vec<String>,
+}
+
+impl PrefixTrie {
+    /// Construye el trie con una lista de prefijos (los duplicados se ignoran)
+    ///
+    /// Los caracteres más allá de `PUBKEY_PREFIX_CHARS` dependen del checksum;
+    /// esos prefijos se insertan truncados y sus aciertos deben confirmarse
+    /// sobre la dirección, como con `PrefixMask`.
+    pub fn new(prefixes: &[String]) -> Result<Self, String> {
+        let mut trie = Self {
+            nodes: vec![TrieNode::new()],
+            prefixes: Vec::new(),
+        };
+
+        for prefix in prefixes {
+            trie.insert(prefix)?;
+        }
+
+        Ok(trie)
+    }
+
+    fn insert(&mut self, prefix: &str) -> Result<(), String> {
+        if prefix.is_empty() {
+            return Err("El prefijo no puede estar vacío".to_string());
+        }
+
+        let mut node = 0usize;
+        for ch in prefix.chars().take(PUBKEY_PREFIX_CHARS) {
+            let symbol = base32_value(ch).ok_or_else(|| {
+                format!(
+                    "Prefijo '{}' contiene caracteres inválidos. Solo se permiten a-z y 2-7",
+                    prefix
+                )
+            })? as usize;
+
+            node = match self.nodes[node].children[symbol] {
+                0 => {
+                    self.nodes.push(TrieNode::new());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children[symbol] = child as u32;
+                    child
+                }
+                child => child as usize,
+            };
+        }
+
+        // Dos prefijos que solo difieren a partir del carácter 52 comparten
+        // nodo; se conserva el primero (encontrarlos es inviable en la práctica)
+        if self.prefixes.iter().any(|existing| existing == prefix) {
+            return Ok(());
+        }
+        if self.nodes[node].terminal.is_none() {
+            self.nodes[node].terminal = Some(self.prefixes.len() as u32);
+        }
+        self.prefixes.push(prefix.to_string());
+        Ok(())
+    }
+
+    /// Prefijos insertados, en orden de inserción
+    pub fn prefixes(&self) -> &[String] {
+        &self.prefixes
+    }
+
+    /// Número de prefijos distintos
+    pub fn len(&self) -> usize {
+        self.prefixes.len()
+    }
+
+    /// Indica si el trie no contiene prefijos
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty()
+    }
+
+    /// Prefijos cuyo camino coincide con los bits de la clave, del más largo
+    /// al más corto
+    ///
+    /// Sin aciertos no reserva memoria: el vector solo crece al encontrar un
+    /// nodo terminal.
+    pub fn matches(&self, public_key: &[u8; 32]) -> Vec<&str> {
+        let mut found = Vec::new();
+        let mut node = 0usize;
+
+        for index in 0..PUBKEY_PREFIX_CHARS {
+            let symbol = key_symbol(public_key, index) as usize;
+            node = match self.nodes[node].children[symbol] {
+                0 => break,
+                child => child as usize,
+            };
+            if let Some(terminal) = self.nodes[node].terminal {
+                found.push(self.prefixes[terminal as usize].as_str());
+            }
+        }
+
+        found.reverse();
+        found
+    }
+}
+
 /// Valida que una dirección .onion v3 tenga el formato correcto
 /// 
 /// # Arguments
@@ -145,18 +656,72 @@ pub fn validate_onion_address(address: &str) -> bool {
 mod tests {
     use super::*;
 
+    /// Deriva la clave pública de una clave expandida con ed25519-dalek
+    fn dalek_public_key(secret: &[u8; 64]) -> [u8; 32] {
+        use ed25519_dalek::hazmat::ExpandedSecretKey;
+        use ed25519_dalek::VerifyingKey;
+
+        VerifyingKey::from(&ExpandedSecretKey::from_bytes(secret)).to_bytes()
+    }
+
     #[test]
-    fn test_generate_keypair() {
-        let (priv1, pub1) = generate_keypair();
-        let (priv2, pub2) = generate_keypair();
-        
-        // Las claves deben ser diferentes
-        assert_ne!(priv1, priv2);
-        assert_ne!(pub1, pub2);
-        
-        // Las claves deben tener 32 bytes
-        assert_eq!(priv1.len(), 32);
-        assert_eq!(pub1.len(), 32);
+    fn test_key_walker_random() {
+        let mut walker1 = KeyWalker::random();
+        let mut walker2 = KeyWalker::random();
+
+        // Los recorridos deben partir de claves diferentes
+        assert_ne!(walker1.secret_key_at(0), walker2.secret_key_at(0));
+        assert_ne!(
+            walker1.next_batch().public_keys,
+            walker2.next_batch().public_keys
+        );
+    }
+
+    #[test]
+    fn test_key_walker_matches_standard_keygen() {
+        use ed25519_dalek::SigningKey;
+
+        let seed = [7u8; 32];
+        let mut walker = KeyWalker::from_seed(&seed);
+        let signing_key = SigningKey::from_bytes(&seed);
+
+        // El primer candidato es la clave Ed25519 estándar de la semilla
+        let batch = walker.next_batch();
+        assert_eq!(batch.first_step, 0);
+        assert_eq!(batch.public_keys[0], signing_key.verifying_key().to_bytes());
+        assert_eq!(walker.secret_key_at(0), expand_secret_key(&seed));
+    }
+
+    #[test]
+    fn test_key_walker_batches() {
+        let mut walker = KeyWalker::from_seed(&[42u8; 32]);
+        walker.next_batch();
+        let batch = walker.next_batch();
+        assert_eq!(batch.first_step, BATCH_SIZE as u64);
+
+        // Muestrear el lote: el escalar debe seguir clamped y coincidir con la
+        // clave pública obtenida por la compresión en lote
+        for i in [0, 1, 2, BATCH_SIZE / 2, BATCH_SIZE - 1] {
+            let secret = walker.secret_key_at(batch.first_step + i as u64);
+
+            let mut scalar = [0u8; 32];
+            scalar.copy_from_slice(&secret[..32]);
+            assert_eq!(scalar, clamp_integer(scalar));
+            assert_eq!(dalek_public_key(&secret), batch.public_keys[i]);
+            assert_eq!(public_key_from_secret(&secret), batch.public_keys[i]);
+        }
+    }
+
+    #[test]
+    fn test_add_scalar_steps_carry() {
+        let mut scalar = [0u8; 32];
+        scalar[0] = 0xf8;
+        scalar[1] = 0xff;
+
+        let result = add_scalar_steps(&scalar, 1);
+        assert_eq!(result[0], 0x00);
+        assert_eq!(result[1], 0x00);
+        assert_eq!(result[2], 0x01);
     }
 
     #[test]
@@ -193,16 +758,145 @@ mod tests {
     }
 
     #[test]
-    fn test_matches_prefix() {
-        let address = "test1234567890abcdefghijklmnopqrstuvwxyz234567abcdefgh.onion";
-        let prefixes = vec!["test".to_string(), "hello".to_string()];
-        
-        let matched = matches_prefix(address, &prefixes);
-        assert_eq!(matched, Some("test".to_string()));
-        
-        let prefixes_no_match = vec!["hello".to_string(), "world".to_string()];
-        let matched = matches_prefix(address, &prefixes_no_match);
-        assert_eq!(matched, None);
+    fn test_prefix_mask_matches_address() {
+        let mut walker = KeyWalker::from_seed(&[3u8; 32]);
+        let batch = walker.next_batch();
+
+        for public_key in batch.public_keys.iter().take(32) {
+            let address = generate_onion_address(public_key);
+
+            // Cualquier prefijo de la propia dirección debe coincidir por bits
+            for len in [1, 2, 5, 13, PUBKEY_PREFIX_CHARS] {
+                let mask = PrefixMask::new(&address[..len]).unwrap();
+                assert!(mask.matches(public_key), "prefijo {}", &address[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefix_mask_rejects() {
+        let public_key = [0u8; 32];
+        let address = generate_onion_address(&public_key);
+        assert!(address.starts_with("aaaa"));
+
+        assert!(PrefixMask::new("aaaa").unwrap().matches(&public_key));
+        assert!(!PrefixMask::new("aaab").unwrap().matches(&public_key));
+        assert!(!PrefixMask::new("baaa").unwrap().matches(&public_key));
+        assert!(PrefixMask::new("aaa1").is_err());
+
+        let masks = PrefixMask::compile_all(&["test".to_string(), "aa".to_string()]).unwrap();
+        let matched = masks.iter().find(|mask| mask.matches(&public_key));
+        assert_eq!(matched.map(|m| m.prefix()), Some("aa"));
+    }
+
+    #[test]
+    fn test_prefix_trie_matches() {
+        let mut walker = KeyWalker::from_seed(&[4u8; 32]);
+        let batch = walker.next_batch();
+
+        for public_key in batch.public_keys.iter().take(32) {
+            let address = generate_onion_address(public_key);
+            let prefixes: Vec<String> = [1, 3, 7, PUBKEY_PREFIX_CHARS]
+                .iter()
+                .map(|&len| address[..len].to_string())
+                .chain(["zzzz".to_string(), "7777".to_string()])
+                .collect();
+            let trie = PrefixTrie::new(&prefixes).unwrap();
+
+            // Del más largo al más corto, sin los que no coinciden
+            let expected: Vec<&str> = [PUBKEY_PREFIX_CHARS, 7, 3, 1]
+                .iter()
+                .map(|&len| &address[..len])
+                .collect();
+            assert_eq!(trie.matches(public_key), expected);
+        }
+    }
+
+    #[test]
+    fn test_prefix_trie_build() {
+        let prefixes: Vec<String> = ["shop", "shop", "sh", "blog"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let trie = PrefixTrie::new(&prefixes).unwrap();
+        assert_eq!(trie.len(), 3);
+
+        assert!(trie.matches(&[0u8; 32]).is_empty());
+        assert!(PrefixTrie::new(&["ab1".to_string()]).is_err());
+        assert!(PrefixTrie::new(&[String::new()]).is_err());
+    }
+
+    #[test]
+    fn test_prefix_matching_oracle() {
+        // La comparación por bits debe decir lo mismo que comparar la
+        // dirección en texto, para claves cualesquiera
+        let prefixes: Vec<String> = ["a", "c", "ab", "abc", "abcd", "q", "qz", "qz7", "m2", "7"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let masks = PrefixMask::compile_all(&prefixes).unwrap();
+        let trie = PrefixTrie::new(&prefixes).unwrap();
+
+        for _ in 0..4096 {
+            let mut public_key = [0u8; 32];
+            OsRng.fill_bytes(&mut public_key);
+            let address = generate_onion_address(&public_key);
+
+            for mask in &masks {
+                assert_eq!(
+                    mask.matches(&public_key),
+                    address.starts_with(mask.prefix()),
+                    "{} / {}",
+                    address,
+                    mask.prefix()
+                );
+            }
+
+            let mut expected: Vec<&str> = prefixes
+                .iter()
+                .map(String::as_str)
+                .filter(|prefix| address.starts_with(prefix))
+                .collect();
+            expected.sort_by_key(|prefix| std::cmp::Reverse(prefix.len()));
+            assert_eq!(trie.matches(&public_key), expected, "{}", address);
+        }
+    }
+
+    #[test]
+    fn test_payload_symbols_match_address() {
+        let mut walker = KeyWalker::from_seed(&[9u8; 32]);
+        let public_key = walker.next_batch().public_keys[0];
+        let address = generate_onion_address(&public_key);
+        let payload = onion_payload(&public_key);
+
+        for (index, ch) in address[..ONION_BODY_CHARS].chars().enumerate() {
+            assert_eq!(payload_symbol(&payload, index), base32_value(ch).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_fixed_tail_symbols() {
+        // El último carácter es siempre 'd'
+        assert_eq!(fixed_symbol_bits(ONION_BODY_CHARS - 1), (0x1f, 3));
+        // El penúltimo tiene sus 3 bits bajos a cero: a, i, q, y
+        assert_eq!(fixed_symbol_bits(ONION_BODY_CHARS - 2), (0x07, 0));
+        // Los demás son libres
+        assert_eq!(fixed_symbol_bits(ONION_BODY_CHARS - 3), (0, 0));
+        assert_eq!(fixed_symbol_bits(0), (0, 0));
+    }
+
+    #[test]
+    fn test_suffix_probability() {
+        assert_eq!(suffix_probability("d"), 1.0);
+        assert_eq!(suffix_probability("id"), 0.25);
+        assert_eq!(suffix_probability("xyd"), 0.25 / 32.0);
+        assert_eq!(suffix_probability("shop"), 0.0);
+        assert_eq!(suffix_probability("bd"), 0.0);
+
+        assert!(explain_impossible_suffix("yd").is_none());
+        assert!(explain_impossible_suffix("shop").unwrap().contains("'d'"));
+        assert!(explain_impossible_suffix("bd").unwrap().contains("'y'"));
+        assert!(explain_impossible_suffix("ab1").is_some());
     }
 
     #[test]
@@ -227,8 +921,8 @@ mod tests {
     #[test]
     fn test_onion_v3_format_integration() {
         // Test de integración: generar una clave y verificar que la dirección sea válida
-        let (_, public_key) = generate_keypair();
-        let address = generate_onion_address(&public_key);
+        let mut walker = KeyWalker::random();
+        let address = generate_onion_address(&walker.next_batch().public_keys[0]);
         
         assert!(validate_onion_address(&address));
         assert_eq!(address.len(), 62); // 56 + ".onion"
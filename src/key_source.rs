@@ -0,0 +1,146 @@
+use crate::generator::KeyWalker;
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::fmt;
+
+/// Origen de las claves que recorre cada worker
+///
+/// La búsqueda pide a la fuente un `KeyWalker` por worker. La fuente por
+/// defecto usa el generador del sistema; la determinista existe solo para
+/// poder reproducir una búsqueda exacta en pruebas.
+pub trait KeySource: Send + Sync + fmt::Debug {
+    /// Nombre descriptivo de la fuente
+    fn name(&self) -> String;
+
+    /// Crea el recorrido de un worker
+    ///
+    /// Tras cada acierto la búsqueda pide un recorrido nuevo (`segment + 1`):
+    /// los candidatos de un mismo recorrido difieren en múltiplos pequeños y
+    /// conocidos de 8, así que no pueden compartirlo dos resultados.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - Índice del worker (0..threads)
+    /// * `segment` - Número de recorrido del worker, empezando en 0
+    /// * `start_step` - Paso desde el que continuar (solo fuentes deterministas)
+    ///
+    /// # Returns
+    ///
+    /// Un `KeyWalker` listo para generar lotes
+    fn walker(&self, worker: usize, segment: u64, start_step: u64) -> KeyWalker;
+
+    /// Indica si las claves son reproducibles (y por tanto inseguras)
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+}
+
+/// Fuente por defecto: semillas aleatorias de OsRng
+#[derive(Debug, Clone, Default)]
+pub struct OsKeySource;
+
+impl KeySource for OsKeySource {
+    fn name(&self) -> String {
+        "OsRng".to_string()
+    }
+
+    /// Cada recorrido parte de una semilla nueva: no hay posición que continuar
+    fn walker(&self, _worker: usize, _segment: u64, _start_step: u64) -> KeyWalker {
+        KeyWalker::random()
+    }
+}
+
+/// Fuente determinista: semillas derivadas de ChaCha20 a partir de un `u64`
+///
+/// Cada worker usa su propio flujo de ChaCha20 y cada recorrido toma los 32
+/// bytes siguientes del flujo, así que con la misma semilla y el mismo número
+/// de hilos se recorren exactamente las mismas claves.
+/// ⚠️ Cualquiera que conozca la semilla puede regenerar las claves privadas:
+/// solo sirve para pruebas.
+#[derive(Debug, Clone)]
+pub struct SeededKeySource {
+    /// Semilla del generador
+    seed: u64,
+}
+
+impl SeededKeySource {
+    /// Crea la fuente con una semilla
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Semilla de 32 bytes del recorrido `segment` de un worker
+    fn worker_seed(&self, worker: usize, segment: u64) -> [u8; 32] {
+        let mut rng = ChaCha20Rng::seed_from_u64(self.seed);
+        rng.set_stream(worker as u64);
+        rng.set_word_pos(segment as u128 * 8);
+
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        seed
+    }
+}
+
+impl KeySource for SeededKeySource {
+    fn name(&self) -> String {
+        format!("ChaCha20 (semilla {})", self.seed)
+    }
+
+    fn walker(&self, worker: usize, segment: u64, start_step: u64) -> KeyWalker {
+        KeyWalker::from_seed_at(&self.worker_seed(worker, segment), start_step)
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_key_source() {
+        let source = SeededKeySource::new(42);
+        assert!(source.is_deterministic());
+
+        // Misma semilla, worker y recorrido: mismas claves; si cambia
+        // cualquiera de ellos, distintas
+        let first = source.walker(0, 0, 0).next_batch().public_keys[0];
+        assert_eq!(
+            SeededKeySource::new(42)
+                .walker(0, 0, 0)
+                .next_batch()
+                .public_keys[0],
+            first
+        );
+        assert_ne!(source.walker(1, 0, 0).next_batch().public_keys[0], first);
+        assert_ne!(source.walker(0, 1, 0).next_batch().public_keys[0], first);
+        assert_ne!(
+            SeededKeySource::new(43)
+                .walker(0, 0, 0)
+                .next_batch()
+                .public_keys[0],
+            first
+        );
+
+        // Continuar desde un paso equivale a haber recorrido los anteriores
+        let mut walker = source.walker(0, 0, 0);
+        walker.next_batch();
+        let second = walker.next_batch();
+        let resumed = source.walker(0, 0, second.first_step).next_batch();
+        assert_eq!(resumed.first_step, second.first_step);
+        assert_eq!(resumed.public_keys, second.public_keys);
+    }
+
+    #[test]
+    fn test_os_key_source() {
+        let source = OsKeySource;
+        assert!(!source.is_deterministic());
+        assert_ne!(
+            source.walker(0, 0, 0).next_batch().public_keys[0],
+            source.walker(0, 0, 0).next_batch().public_keys[0]
+        );
+    }
+}
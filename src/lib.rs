@@ -0,0 +1,29 @@
+//! Generación de direcciones .onion v3 vanity como biblioteca
+//!
+//! Los dos binarios (`torge-forge` y `torge-forge-cli`) son clientes de esta
+//! biblioteca. Para integrarla en otro servicio, `Searcher` reúne patrones,
+//! hilos, límites, fuente de claves y destinos de los resultados, y avisa del
+//! progreso a un `SearchObserver`.
+//!
+//! Con la feature `async`, `stream::search_stream` entrega los eventos de la
+//! búsqueda como un `futures::Stream` sin bloquear el runtime.
+
+pub mod bench;
+pub mod checkpoint;
+pub mod dictionary;
+pub mod difficulty;
+pub mod distributed;
+pub mod error;
+mod field;
+pub mod generator;
+pub mod key_source;
+pub mod matcher;
+pub mod reporter;
+pub mod scoring;
+pub mod search;
+pub mod searcher;
+pub mod split_key;
+pub mod storage;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod types;
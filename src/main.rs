@@ -1,21 +1,50 @@
 mod cli;
-mod error;
-mod generator;
-mod search;
-mod storage;
-mod types;
 
 use clap::Parser;
-use cli::Cli;
-use search::search_vanity;
+use cli::{Cli, Command};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use vanity_onion_v3::search::search_vanity;
+use vanity_onion_v3::{
+    bench, checkpoint, distributed, error, matcher, reporter, scoring, search, split_key, storage, types,
+};
 
 fn main() {
     // Parsear argumentos de línea de comandos
     let cli = Cli::parse();
 
+    // Subcomandos de clave dividida y de medición: no hacen búsqueda
+    match cli.command.clone() {
+        Some(Command::Generate {
+            prefixes,
+            max_results,
+            job,
+            secret,
+        }) => {
+            run_generate(&prefixes, max_results, &job, &secret);
+            return;
+        }
+        Some(Command::Combine {
+            job,
+            secret,
+            offsets,
+            output_dir,
+        }) => {
+            run_combine(&job, &secret, &offsets, &output_dir);
+            return;
+        }
+        Some(Command::Bench {
+            max_threads,
+            seconds,
+            output,
+        }) => {
+            run_bench(max_threads, seconds, output);
+            return;
+        }
+        None => {}
+    }
+
     // Validar argumentos
     if let Err(e) = cli.validate() {
         eprintln!("❌ Error de validación: {}", e);
@@ -24,24 +53,99 @@ fn main() {
 
     // Guardar valores antes de mover cli
     let quiet = cli.quiet;
+    let compact = cli.report_options().map(|report| report.style) == Some(reporter::ReportStyle::Compact);
     
-    // Mostrar configuración
-    if !quiet {
+    // Mostrar configuración (un worker la recibe del coordinador)
+    if !quiet && cli.worker.is_none() {
         cli.print_config();
     }
+    if cli.estimate && cli.worker.is_none() {
+        cli.print_estimates();
+    }
 
-    // Configurar manejador de señales para Ctrl+C
+    // Advertir siempre (también en modo silencioso) de las claves inseguras
+    if let Some(seed) = cli.seed {
+        eprintln!(
+            "⚠️  ⚠️  ⚠️  ADVERTENCIA: FUENTE DE CLAVES DETERMINISTA (--seed {}) ⚠️  ⚠️  ⚠️",
+            seed
+        );
+        eprintln!("   Las claves se derivan de la semilla: quien la conozca puede regenerarlas.");
+        eprintln!("   Úselo solo para pruebas; NUNCA para un servicio oculto real.");
+        if cli.insecure_allow_seeded_keys {
+            eprintln!(
+                "   🔓 --insecure-allow-seeded-keys: las claves inseguras SE GUARDARÁN en disco."
+            );
+        }
+        eprintln!();
+    }
+
+    // Configurar manejador de señales para Ctrl+C: detiene a los workers de
+    // la búsqueda local (cancel) y al coordinador o worker distribuido (running)
     let running = Arc::new(AtomicBool::new(true));
+    let cancel = types::CancelToken::new();
     let r = running.clone();
+    let c = cancel.clone();
     
     ctrlc::set_handler(move || {
         println!("\n\n⚠️  Recibida señal de interrupción. Deteniendo búsqueda...");
         r.store(false, Ordering::Relaxed);
+        c.cancel();
     })
     .expect("Error al configurar manejador de Ctrl+C");
 
+    // Modo worker: la búsqueda la decide el coordinador
+    if cli.worker.is_some() {
+        match cli.worker_options() {
+            Ok(options) => run_worker(options, &running),
+            Err(e) => {
+                eprintln!("❌ Error de validación: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Búsqueda distribuida: dirección, clave y trabajo (antes de mover cli)
+    let coordinator = match &cli.coordinator {
+        Some(addr) => match cli.cluster_key().and_then(|key| Ok((addr.clone(), key, cli.job()?))) {
+            Ok(coordinator) => Some(coordinator),
+            Err(e) => {
+                eprintln!("❌ Error de validación: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let stats_interval = Duration::from_secs(cli.stats_interval);
+
+    // Criterio del modo puntuación (antes de mover cli)
+    let top = cli.top;
+    let scorer = match top.map(|_| cli.scorer()).transpose() {
+        Ok(scorer) => scorer,
+        Err(e) => {
+            eprintln!("❌ Error de validación: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Trabajo de clave dividida: los resultados son desplazamientos (antes de mover cli)
+    let split_job = match cli.split_job.as_deref().map(split_key::SplitJob::load) {
+        Some(Ok(job)) => Some(job),
+        Some(Err(e)) => {
+            eprintln!("❌ Error de validación: {}", e);
+            std::process::exit(1);
+        }
+        None => None,
+    };
+
     // Convertir CLI a Config
-    let config = cli.into_config();
+    let config = match cli.into_config() {
+        Ok(config) => types::Config { cancel, ..config },
+        Err(e) => {
+            eprintln!("❌ Error de validación: {}", e);
+            std::process::exit(1);
+        }
+    };
     let output_dir = config.output_dir.clone();
     let dry_run = config.dry_run;
     let threads = config.threads;
@@ -53,32 +157,61 @@ fn main() {
             std::process::exit(1);
         }
 
-        // Crear README en el directorio de salida
-        if let Err(e) = storage::create_readme(&output_dir) {
-            eprintln!("⚠️  Advertencia: No se pudo crear README: {}", e);
+        // Crear README en el directorio de salida (no habrá claves con --split-job)
+        if split_job.is_none() {
+            if let Err(e) = storage::create_readme(&output_dir) {
+                eprintln!("⚠️  Advertencia: No se pudo crear README: {}", e);
+            }
         }
     }
 
+    if let Some((addr, key, job)) = coordinator {
+        run_coordinator(&addr, &key, job, &config, quiet, stats_interval, &running);
+        return;
+    }
+
+    if let (Some(top), Some(scorer)) = (top, scorer) {
+        run_top(&config, scorer, top);
+        return;
+    }
+
+    if let Some(checkpoint) = &config.resume {
+        println!(
+            "♻️  Reanudando búsqueda: {} intentos y {} resultados en {:.0}s",
+            checkpoint.total_attempts, checkpoint.results_found, checkpoint.elapsed_seconds
+        );
+    }
+
     println!("🚀 Iniciando búsqueda...");
     println!();
 
-    let start_time = Instant::now();
-
     // Ejecutar búsqueda
+    // Tras Ctrl+C los resultados ya encontrados siguen llegando y se guardan
     let search_result = search_vanity(&config, |result| {
-        // Verificar si debemos detenernos
-        if !running.load(Ordering::Relaxed) {
-            return Err(error::VanityError::Cancelled);
+        // Mostrar resultado encontrado (sobre la línea del progreso compacto)
+        if compact {
+            reporter::clear_compact_line();
         }
-
-        // Mostrar resultado encontrado
         println!(
             "✅ [ENCONTRADO] prefijo=\"{}\" dirección=\"{}\"",
             result.matched_prefix, result.address
         );
+        if let Some(word) = &result.dictionary_word {
+            println!("   📖 Palabra: {}", word);
+        }
 
-        // Guardar en disco si no es dry-run
-        if !dry_run {
+        // Con clave dividida solo se guarda el desplazamiento para el solicitante
+        if let Some(job) = &split_job {
+            if dry_run {
+                println!("   🔍 Modo dry-run: no se guardó el desplazamiento");
+            } else {
+                let path = output_dir.join(split_key::OFFSETS_FILE);
+                match split_key::SplitOffset::from_result(job, &result).append(&path) {
+                    Ok(()) => println!("   🧩 Desplazamiento añadido a: {}", path.display()),
+                    Err(e) => eprintln!("   ⚠️  Error al guardar: {}", e),
+                }
+            }
+        } else if !dry_run {
             match storage::save_result(&result, &output_dir, threads) {
                 Ok(path) => {
                     println!("   💾 Guardado en: {}", path.display());
@@ -99,26 +232,60 @@ fn main() {
     // Manejar resultado de la búsqueda
     match search_result {
         Ok(stats) => {
-            let elapsed = start_time.elapsed();
-            
-            println!("╔════════════════════════════════════════════════════════════════╗");
-            println!("║                      BÚSQUEDA COMPLETADA                       ║");
-            println!("╚════════════════════════════════════════════════════════════════╝");
+            match stats.stop_reason {
+                types::StopReason::GoalReached => {
+                    println!("╔════════════════════════════════════════════════════════════════╗");
+                    println!("║                      BÚSQUEDA COMPLETADA                       ║");
+                    println!("╚════════════════════════════════════════════════════════════════╝");
+                }
+                types::StopReason::MaxAttempts => {
+                    println!("⚠️  Se alcanzó el límite máximo de intentos.");
+                }
+                types::StopReason::Deadline => {
+                    println!("⏰ Se alcanzó el límite de tiempo: búsqueda detenida.");
+                }
+                types::StopReason::Cancelled => {
+                    println!("⚠️  Búsqueda cancelada por el usuario.");
+                }
+            }
             println!();
             println!("📊 Estadísticas finales:");
             println!("   • Resultados encontrados: {}", stats.results_found);
             println!("   • Total de intentos: {}", stats.total_attempts);
-            println!("   • Tiempo transcurrido: {:.2}s", elapsed.as_secs_f64());
+            println!("   • Tiempo transcurrido: {:.2}s", stats.elapsed_seconds);
             println!("   • Tasa promedio: {:.2} intentos/s", stats.rate());
             
+            for pattern in &stats.patterns {
+                match pattern.quota {
+                    Some(quota) => {
+                        println!("   • Cupo '{}': {}/{}", pattern.label, pattern.found, quota)
+                    }
+                    None => println!("   • Patrón '{}': {}", pattern.label, pattern.found),
+                }
+            }
+
             if stats.results_found > 0 {
                 let avg_attempts = stats.total_attempts as f64 / stats.results_found as f64;
                 println!("   • Promedio de intentos por resultado: {:.0}", avg_attempts);
             }
+            for line in reporter::luck_summary(
+                config.matcher.probability(),
+                stats.total_attempts,
+                stats.results_found,
+                stats.rate(),
+            ) {
+                println!("   • {}", line);
+            }
             
             println!();
 
-            if !dry_run && stats.results_found > 0 {
+            if split_job.is_some() && !dry_run && stats.results_found > 0 {
+                println!(
+                    "🧩 Envíe {} al solicitante: solo él puede combinarlo (combine)",
+                    output_dir.join(split_key::OFFSETS_FILE).display()
+                );
+                println!();
+            } else if !dry_run && stats.results_found > 0 {
                 println!("📁 Archivos guardados en: {}", output_dir.display());
                 println!();
                 println!("⚠️  IMPORTANTE: Guarda las claves privadas de forma segura.");
@@ -141,4 +308,365 @@ fn main() {
             }
         }
     }
+
+    // Indicar cómo continuar si la búsqueda quedó a medias
+    let checkpoint_path = checkpoint::Checkpoint::path(&output_dir);
+    if config.checkpoint_interval.is_some() && checkpoint_path.exists() {
+        println!(
+            "♻️  Progreso guardado en {}. Continúe con --resume",
+            checkpoint_path.display()
+        );
+    }
+}
+
+/// Modo puntuación: busca durante el presupuesto y guarda solo las ganadoras
+fn run_top(
+    config: &types::Config,
+    scorer: Arc<dyn scoring::Scorer>,
+    top: usize,
+) {
+    println!(
+        "🏆 Buscando las {} mejores direcciones ({})...",
+        top,
+        scorer.name()
+    );
+    println!();
+
+    let (winners, outcome) = scoring::search_top(config, scorer, top, |entry| {
+        println!(
+            "⭐ [TOP] puntuación={} etiqueta=\"{}\" dirección=\"{}\"",
+            entry.score, entry.result.matched_prefix, entry.result.address
+        );
+        Ok(())
+    });
+
+    match &outcome {
+        Ok(stats) => {
+            println!();
+            if stats.stop_reason == types::StopReason::Cancelled {
+                println!("⚠️  Búsqueda cancelada por el usuario: se conservan las ganadoras hasta ahora.");
+            }
+            println!(
+                "📊 Intentos: {} | Tasa: {:.2} intentos/s",
+                stats.total_attempts,
+                stats.rate()
+            );
+        }
+        Err(e) => {
+            eprintln!("❌ Error durante la búsqueda: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    println!();
+    println!("🏆 Ganadoras:");
+    for (position, entry) in winners.iter().enumerate() {
+        println!(
+            "   {}. [{}] {}",
+            position + 1,
+            entry.score,
+            entry.result.address
+        );
+
+        if config.dry_run {
+            continue;
+        }
+        match storage::save_result(&entry.result, &config.output_dir, config.threads) {
+            Ok(path) => println!("      💾 Guardado en: {}", path.display()),
+            Err(e) => eprintln!("      ⚠️  Error al guardar: {}", e),
+        }
+    }
+
+    if winners.is_empty() {
+        println!("   (ninguna)");
+    } else if config.dry_run {
+        println!();
+        println!("🔍 Modo dry-run: no se guardó nada en disco");
+    }
+    println!();
+}
+
+/// Modo coordinador: reparte la búsqueda y guarda los resultados de los workers
+fn run_coordinator(
+    addr: &str,
+    key: &[u8],
+    job: distributed::Job,
+    config: &types::Config,
+    quiet: bool,
+    stats_interval: Duration,
+    running: &AtomicBool,
+) {
+    let coordinator = match distributed::Coordinator::bind(addr, key, job) {
+        Ok(coordinator) => coordinator,
+        Err(e) => {
+            eprintln!("❌ Error al iniciar el coordinador en {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
+    if let Ok(local_addr) = coordinator.local_addr() {
+        println!(
+            "📡 Coordinador escuchando en {} (protocolo v{})",
+            local_addr,
+            distributed::PROTOCOL_VERSION
+        );
+    }
+    println!("   Workers: torge-forge-cli --worker <host:puerto> --cluster-key-file <fichero>");
+    println!("⚠️  Quien tenga la clave del clúster puede unirse como worker: guárdela como una clave privada");
+    println!();
+
+    let mut last_stats = Instant::now();
+    let outcome = coordinator.run(running, |event| {
+        match event {
+            distributed::CoordinatorEvent::Connected { worker } => {
+                println!("🔌 Worker conectado: {}", worker);
+            }
+            distributed::CoordinatorEvent::Disconnected { worker } => {
+                println!("🔌 Worker desconectado: {}", worker);
+            }
+            distributed::CoordinatorEvent::Rejected { peer, reason } => {
+                eprintln!("⛔ Conexión rechazada de {}: {}", peer, reason);
+            }
+            distributed::CoordinatorEvent::InvalidResult { worker, reason } => {
+                eprintln!("⚠️  Resultado inválido de {}: {}", worker, reason);
+            }
+            distributed::CoordinatorEvent::Progress { stats } => {
+                if !quiet && last_stats.elapsed() >= stats_interval {
+                    search::print_stats(&stats);
+                    last_stats = Instant::now();
+                }
+            }
+            distributed::CoordinatorEvent::Found { worker, result } => {
+                println!(
+                    "✅ [ENCONTRADO] worker=\"{}\" prefijo=\"{}\" dirección=\"{}\"",
+                    worker, result.matched_prefix, result.address
+                );
+                if config.dry_run {
+                    println!("   🔍 Modo dry-run: no se guardó en disco");
+                } else {
+                    match storage::save_result(&result, &config.output_dir, config.threads) {
+                        Ok(path) => println!("   💾 Guardado en: {}", path.display()),
+                        Err(e) => eprintln!("   ⚠️  Error al guardar: {}", e),
+                    }
+                }
+                println!();
+            }
+        }
+        Ok(())
+    });
+
+    match outcome {
+        Ok(stats) => {
+            println!("📊 Búsqueda distribuida completada:");
+            println!("   • Resultados encontrados: {}", stats.results_found);
+            println!("   • Total de intentos (todos los workers): {}", stats.total_attempts);
+            println!("   • Tiempo transcurrido: {:.2}s", stats.elapsed_seconds);
+            println!("   • Tasa combinada: {:.2} intentos/s", stats.rate());
+            println!();
+        }
+        Err(error::VanityError::Cancelled) => {
+            println!("⚠️  Búsqueda cancelada por el usuario. Se ha detenido a los workers.");
+        }
+        Err(e) => {
+            eprintln!("❌ Error durante la búsqueda: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Modo worker: busca para un coordinador hasta que este lo detenga
+fn run_worker(options: distributed::WorkerOptions, running: &AtomicBool) {
+    println!(
+        "🛠️  Worker '{}' conectando con {} ({} hilos)...",
+        options.name, options.coordinator, options.threads
+    );
+
+    let outcome = distributed::run_worker(&options, running, |event| match event {
+        distributed::WorkerEvent::Connected { job } => {
+            let patterns = job
+                .matcher()
+                .map(|matcher| matcher.name().to_string())
+                .unwrap_or_default();
+            println!("🔌 Conectado. Buscando: {}", patterns);
+        }
+        distributed::WorkerEvent::Found { address } => {
+            println!("✅ [ENCONTRADO] {} (enviado al coordinador)", address);
+        }
+        distributed::WorkerEvent::Disconnected { reason, retry_in } => {
+            eprintln!(
+                "⚠️  Conexión perdida ({}). Reintentando en {}s...",
+                reason,
+                retry_in.as_secs()
+            );
+        }
+    });
+
+    match outcome {
+        Ok(stats) => {
+            println!(
+                "🏁 El coordinador dio la búsqueda por terminada: {} intentos, {} resultados enviados",
+                stats.total_attempts, stats.results_found
+            );
+        }
+        Err(error::VanityError::Cancelled) => {
+            println!("⚠️  Worker detenido por el usuario.");
+        }
+        Err(e) => {
+            eprintln!("❌ Error del worker: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Subcomando `generate`: crea el par de claves y el trabajo de clave dividida
+fn run_generate(
+    prefixes: &[String],
+    max_results: usize,
+    job_path: &std::path::Path,
+    secret_path: &std::path::Path,
+) {
+    if let Err(e) = matcher::PrefixMatcher::new(prefixes) {
+        eprintln!("❌ Error de validación: {}", e);
+        std::process::exit(1);
+    }
+    if max_results == 0 {
+        eprintln!("❌ Error de validación: El número máximo de resultados debe ser mayor que 0");
+        std::process::exit(1);
+    }
+
+    // El secreto primero: si ya existe no se genera un trabajo huérfano
+    let (job, secret) = split_key::SplitJob::create(prefixes, max_results);
+    if let Err(e) = secret.save(secret_path) {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = job.save(job_path) {
+        eprintln!("❌ Error al guardar el trabajo: {}", e);
+        std::process::exit(1);
+    }
+
+    println!("🔑 Secreto guardado en: {} (NO lo comparta)", secret_path.display());
+    println!("📦 Trabajo guardado en: {}", job_path.display());
+    println!();
+    println!("   1. Envíe el trabajo a la máquina que buscará:");
+    println!("      torge-forge-cli --split-job {}", job_path.display());
+    println!("   2. Recoja su split-offsets.jsonl y combínelo aquí:");
+    println!(
+        "      torge-forge-cli combine --secret {} --offsets split-offsets.jsonl",
+        secret_path.display()
+    );
+}
+
+/// Subcomando `combine`: obtiene las claves finales y las guarda como siempre
+fn run_combine(
+    job_path: &std::path::Path,
+    secret_path: &std::path::Path,
+    offsets_path: &std::path::Path,
+    output_dir: &std::path::Path,
+) {
+    let loaded = split_key::SplitJob::load(job_path).and_then(|job| {
+        let secret = split_key::SplitSecret::load(secret_path)?;
+        Ok((job, secret, split_key::SplitOffset::load_all(offsets_path)?))
+    });
+    let (job, secret, offsets) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = std::fs::create_dir_all(output_dir) {
+        eprintln!("❌ Error al crear directorio de salida: {}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = storage::create_readme(output_dir) {
+        eprintln!("⚠️  Advertencia: No se pudo crear README: {}", e);
+    }
+
+    let mut combined = 0;
+    for offset in &offsets {
+        let result = match split_key::combine(&job, &secret, offset) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("⚠️  Desplazamiento descartado: {}", e);
+                continue;
+            }
+        };
+
+        println!(
+            "✅ [COMBINADO] prefijo=\"{}\" dirección=\"{}\"",
+            result.matched_prefix, result.address
+        );
+        match storage::save_result(&result, output_dir, 1) {
+            Ok(path) => {
+                println!("   💾 Guardado en: {}", path.display());
+                combined += 1;
+            }
+            Err(e) => eprintln!("   ⚠️  Error al guardar: {}", e),
+        }
+    }
+
+    println!();
+    println!(
+        "📊 {} de {} desplazamientos combinados",
+        combined,
+        offsets.len()
+    );
+    if combined > 0 {
+        println!("⚠️  IMPORTANTE: Guarda las claves privadas de forma segura.");
+    } else {
+        std::process::exit(1);
+    }
+}
+
+/// Subcomando `bench`: mide la tasa con 1..N hilos y guarda la calibración
+fn run_bench(max_threads: usize, seconds: u64, output: Option<std::path::PathBuf>) {
+    if max_threads == 0 || seconds == 0 {
+        eprintln!("❌ Error de validación: --max-threads y --seconds deben ser mayores que 0");
+        std::process::exit(1);
+    }
+    let Some(path) = output.or_else(bench::Calibration::default_path) else {
+        eprintln!("❌ No se encontró un directorio de configuración: indique --output");
+        std::process::exit(1);
+    };
+
+    let counts = bench::bench_thread_counts(max_threads);
+    println!(
+        "🏁 Midiendo la tasa de claves con {} configuraciones de hilos ({}s cada una)...",
+        counts.len(),
+        seconds
+    );
+    println!();
+    println!("   {:>5}  {:>14}  {:>11}  {:>10}", "hilos", "intentos/s", "aceleración", "eficiencia");
+
+    let mut points = Vec::new();
+    for threads in counts {
+        let rate = match bench::measure_rate(threads, Duration::from_secs(seconds)) {
+            Ok(rate) => rate,
+            Err(e) => {
+                eprintln!("❌ Error durante la medición: {}", e);
+                std::process::exit(1);
+            }
+        };
+        let single = points.first().map_or(rate, |first: &bench::BenchPoint| first.rate);
+        let speedup = rate / single;
+        println!(
+            "   {:>5}  {:>14.0}  {:>10.2}x  {:>9.0}%  {}",
+            threads,
+            rate,
+            speedup,
+            speedup / threads as f64 * 100.0,
+            "█".repeat(speedup.round().max(1.0) as usize)
+        );
+        points.push(bench::BenchPoint { threads, rate });
+    }
+
+    let calibration = bench::Calibration::new(points, seconds as f64);
+    if let Err(e) = calibration.save(&path) {
+        eprintln!("❌ Error al guardar la calibración: {}", e);
+        std::process::exit(1);
+    }
+    println!();
+    println!("💾 Calibración guardada en: {}", path.display());
+    println!("   Las estimaciones de tiempo (--estimate y el modo interactivo) la usarán automáticamente.");
 }
@@ -1,23 +1,62 @@
-mod error;
-mod generator;
-mod search;
-mod storage;
-mod types;
 mod ui;
 
-use search::search_vanity;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use ui::{
     interactive_config, pause, read_line, show_banner, show_final_stats, show_help,
     show_main_menu, show_result_found, show_search_header, show_time_estimation,
 };
+use vanity_onion_v3::search::search_vanity;
+use vanity_onion_v3::types::CancelToken;
+use vanity_onion_v3::{error, storage, types};
+
+/// Búsqueda del menú en curso, la que detiene Ctrl+C
+///
+/// ctrlc solo admite un manejador por proceso: se instala una vez en `main`
+/// y cada búsqueda registra aquí su propia señal de cancelación.
+#[derive(Clone, Default)]
+struct ActiveSearch(Arc<Mutex<Option<CancelToken>>>);
+
+impl ActiveSearch {
+    /// Registra la señal de la búsqueda que empieza
+    fn start(&self, cancel: CancelToken) {
+        *self.0.lock().unwrap() = Some(cancel);
+    }
+
+    /// Olvida la búsqueda que acaba de terminar
+    fn finish(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+
+    /// Cancela la búsqueda en curso; false si no hay ninguna
+    fn interrupt(&self) -> bool {
+        match self.0.lock().unwrap().as_ref() {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
 
 fn main() {
     // Mostrar banner
     show_banner();
 
+    // Ctrl+C detiene la búsqueda en curso; fuera de una búsqueda, sale
+    let active = ActiveSearch::default();
+    let handler_active = active.clone();
+    ctrlc::set_handler(move || {
+        if handler_active.interrupt() {
+            println!("\n\n⚠️  Recibida señal de interrupción. Deteniendo búsqueda...");
+        } else {
+            println!("\n\n👋 ¡Gracias por usar Torge Forge!\n");
+            std::process::exit(130);
+        }
+    })
+    .expect("Error al configurar manejador de Ctrl+C");
+
     // Loop principal del menú
     loop {
         show_main_menu();
@@ -25,7 +64,7 @@ fn main() {
         let choice = read_line();
 
         match choice.as_str() {
-            "1" => generate_vanity_address(),
+            "1" => generate_vanity_address(&active),
             "2" => show_time_estimation(),
             "3" => {
                 println!("\n⚙️  Configuración avanzada disponible en modo generación (opción 1)\n");
@@ -46,25 +85,22 @@ fn main() {
     }
 }
 
-fn generate_vanity_address() {
+fn generate_vanity_address(active: &ActiveSearch) {
     // Obtener configuración interactiva
-    let config = interactive_config();
+    let config = match interactive_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("\n❌ Error: {}\n", e);
+            pause();
+            return;
+        }
+    };
 
     let output_dir = config.output_dir.clone();
     let dry_run = config.dry_run;
     let threads = config.threads;
     let max_results = config.max_results;
 
-    // Configurar manejador de señales para Ctrl+C
-    let running = Arc::new(AtomicBool::new(true));
-    let r = running.clone();
-
-    ctrlc::set_handler(move || {
-        println!("\n\n⚠️  Recibida señal de interrupción. Deteniendo búsqueda...");
-        r.store(false, Ordering::Relaxed);
-    })
-    .expect("Error al configurar manejador de Ctrl+C");
-
     // Crear directorio de salida si no existe (y no es dry-run)
     if !dry_run {
         if let Err(e) = std::fs::create_dir_all(&output_dir) {
@@ -85,12 +121,9 @@ fn generate_vanity_address() {
     let mut results_count = 0;
 
     // Ejecutar búsqueda
+    // Tras Ctrl+C los resultados ya encontrados siguen llegando y se guardan
+    active.start(config.cancel.clone());
     let search_result = search_vanity(&config, |result| {
-        // Verificar si debemos detenernos
-        if !running.load(Ordering::Relaxed) {
-            return Err(error::VanityError::Cancelled);
-        }
-
         results_count += 1;
 
         // Mostrar resultado encontrado
@@ -117,16 +150,30 @@ fn generate_vanity_address() {
 
         Ok(())
     });
+    active.finish();
 
     // Manejar resultado de la búsqueda
     match search_result {
         Ok(stats) => {
             let elapsed = start_time.elapsed();
+            match stats.stop_reason {
+                types::StopReason::Cancelled => {
+                    println!("\n⚠️  Búsqueda cancelada por el usuario.");
+                }
+                types::StopReason::MaxAttempts => {
+                    println!("\n⚠️  Se alcanzó el límite máximo de intentos.");
+                }
+                types::StopReason::Deadline => {
+                    println!("\n⏰ Se alcanzó el límite de tiempo.");
+                }
+                types::StopReason::GoalReached => {}
+            }
 
             show_final_stats(
                 stats.results_found,
                 stats.total_attempts,
                 elapsed.as_secs_f64(),
+                config.matcher.probability(),
                 &output_dir.display().to_string(),
                 dry_run,
             );
@@ -146,3 +193,28 @@ fn generate_vanity_address() {
 
     pause();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_search_back_to_back() {
+        let active = ActiveSearch::default();
+        assert!(!active.interrupt());
+
+        // Primera búsqueda del menú, terminada sin interrumpir
+        let first = CancelToken::new();
+        active.start(first.clone());
+        active.finish();
+
+        // Ctrl+C en la segunda llega a la señal de la segunda
+        let second = CancelToken::new();
+        active.start(second.clone());
+        assert!(active.interrupt());
+        assert!(second.is_cancelled());
+        assert!(!first.is_cancelled());
+        active.finish();
+        assert!(!active.interrupt());
+    }
+}
@@ -0,0 +1,737 @@
+use crate::dictionary::DictionaryWord;
+use crate::generator::{
+    base32_value, explain_impossible_suffix, fixed_symbol_bits, generate_onion_address,
+    onion_payload, payload_symbol, suffix_probability, PrefixMask, PrefixTrie, BASE32_ALPHABET,
+    ONION_BODY_CHARS,
+};
+use regex::Regex;
+use regex_automata::dfa::{dense, Automaton};
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::start;
+use regex_automata::Anchored;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// Candidato evaluado por un `Matcher` durante la búsqueda
+///
+/// La dirección .onion solo se construye si algún matcher la pide, de modo que
+/// los matchers que trabajan sobre los bits de la clave no pagan el coste del
+/// checksum ni de la codificación base32.
+pub struct Candidate<'a> {
+    /// Clave pública Ed25519 del candidato
+    public_key: &'a [u8; 32],
+    /// Dirección .onion, calculada bajo demanda
+    address: OnceCell<String>,
+}
+
+impl<'a> Candidate<'a> {
+    /// Crea un candidato a partir de su clave pública
+    pub fn new(public_key: &'a [u8; 32]) -> Self {
+        Self {
+            public_key,
+            address: OnceCell::new(),
+        }
+    }
+
+    /// Clave pública Ed25519 (32 bytes)
+    pub fn public_key(&self) -> &[u8; 32] {
+        self.public_key
+    }
+
+    /// Dirección .onion completa (incluyendo ".onion")
+    pub fn address(&self) -> &str {
+        self.address
+            .get_or_init(|| generate_onion_address(self.public_key))
+    }
+
+    /// Parte base32 de la dirección (56 caracteres, sin ".onion")
+    pub fn address_body(&self) -> &str {
+        let address = self.address();
+        &address[..address.len() - ".onion".len()]
+    }
+
+    /// Consume el candidato devolviendo su dirección .onion
+    pub fn into_address(self) -> String {
+        match self.address.into_inner() {
+            Some(address) => address,
+            None => generate_onion_address(self.public_key),
+        }
+    }
+}
+
+/// Regla que decide qué direcciones acepta la búsqueda
+///
+/// `search_vanity` evalúa cada candidato con el matcher de la configuración;
+/// la etiqueta devuelta por `matches` se guarda en `VanityResult::matched_prefix`.
+pub trait Matcher: Send + Sync + fmt::Debug {
+    /// Nombre descriptivo de la regla (para mensajes y estimaciones)
+    fn name(&self) -> &str;
+
+    /// Evalúa un candidato y devuelve la etiqueta que coincidió, si la hay
+    fn matches(&self, candidate: &Candidate) -> Option<String>;
+
+    /// Probabilidad de que una clave aleatoria cumpla la regla
+    fn probability(&self) -> f64;
+
+    /// Palabra de diccionario asociada a una etiqueta devuelta por `matches`
+    ///
+    /// Solo la implementan las reglas basadas en diccionario; el resultado se
+    /// guarda en los metadatos junto al prefijo.
+    fn dictionary_word(&self, _label: &str) -> Option<String> {
+        None
+    }
+
+    /// Variante de la regla que ya no produce las etiquetas indicadas
+    ///
+    /// La búsqueda la usa para dejar de comprobar patrones cuyo cupo está
+    /// cubierto. None significa que la regla no sabe excluir etiquetas; la
+    /// búsqueda sigue entonces con ella y descarta los aciertos sobrantes.
+    fn without_labels(&self, _labels: &HashSet<String>) -> Option<Arc<dyn Matcher>> {
+        None
+    }
+}
+
+/// Matcher de prefijos sobre los bits de la clave pública
+///
+/// Los prefijos se decodifican a máscaras de bits al construirlo; solo los
+/// aciertos por bits llegan a construir la dirección para confirmarse. Con
+/// muchos prefijos (por ejemplo, cargados de un fichero) se usa un trie, cuyo
+/// coste por candidato no crece con el número de prefijos. Si coinciden varios
+/// prefijos, la etiqueta es el más largo.
+#[derive(Debug, Clone)]
+pub struct PrefixMatcher {
+    /// Nombre descriptivo
+    name: String,
+    /// Prefijos decodificados
+    index: PrefixIndex,
+}
+
+/// Representación de los prefijos usada para el descarte por bits
+#[derive(Debug, Clone)]
+enum PrefixIndex {
+    /// Pocos prefijos: una máscara por prefijo
+    Masks(Vec<PrefixMask>),
+    /// Muchos prefijos: trie sobre los símbolos de la clave
+    Trie(PrefixTrie),
+}
+
+impl PrefixMatcher {
+    /// A partir de este número de prefijos se usa el trie
+    pub const TRIE_THRESHOLD: usize = 16;
+
+    /// Número de prefijos que se muestran en el nombre del matcher
+    const NAME_PREFIXES: usize = 5;
+
+    /// Crea el matcher decodificando los prefijos a máscaras de bits o a trie
+    pub fn new(prefixes: &[String]) -> Result<Self, String> {
+        let index = if prefixes.len() >= Self::TRIE_THRESHOLD {
+            PrefixIndex::Trie(PrefixTrie::new(prefixes)?)
+        } else {
+            PrefixIndex::Masks(PrefixMask::compile_all(prefixes)?)
+        };
+
+        let name = if prefixes.len() > Self::NAME_PREFIXES {
+            format!(
+                "{} prefijos ({}, ...)",
+                prefixes.len(),
+                prefixes[..Self::NAME_PREFIXES].join(", ")
+            )
+        } else {
+            format!("prefijo {}", prefixes.join(", "))
+        };
+
+        Ok(Self { name, index })
+    }
+
+    /// Prefijos buscados
+    pub fn prefixes(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        match &self.index {
+            PrefixIndex::Masks(masks) => Box::new(masks.iter().map(PrefixMask::prefix)),
+            PrefixIndex::Trie(trie) => Box::new(trie.prefixes().iter().map(String::as_str)),
+        }
+    }
+}
+
+impl Matcher for PrefixMatcher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, candidate: &Candidate) -> Option<String> {
+        // Descarte rápido sobre los bits de la clave
+        let mut hits = match &self.index {
+            PrefixIndex::Masks(masks) => masks
+                .iter()
+                .filter(|mask| mask.matches(candidate.public_key()))
+                .map(PrefixMask::prefix)
+                .collect(),
+            PrefixIndex::Trie(trie) => trie.matches(candidate.public_key()),
+        };
+        hits.sort_by_key(|prefix| std::cmp::Reverse(prefix.len()));
+
+        // Confirmar sobre la dirección: los caracteres a partir del 52
+        // dependen del checksum y no están en la máscara
+        hits.into_iter()
+            .find(|prefix| candidate.address().starts_with(prefix))
+            .map(str::to_string)
+    }
+
+    fn without_labels(&self, labels: &HashSet<String>) -> Option<Arc<dyn Matcher>> {
+        let remaining: Vec<String> = self
+            .prefixes()
+            .filter(|prefix| !labels.contains(*prefix))
+            .map(str::to_string)
+            .collect();
+
+        if remaining.is_empty() {
+            return Some(Arc::new(AnyMatcher::new(Vec::new())));
+        }
+        PrefixMatcher::new(&remaining)
+            .ok()
+            .map(|matcher| Arc::new(matcher) as Arc<dyn Matcher>)
+    }
+
+    /// Probabilidad de la unión de los prefijos
+    ///
+    /// Un prefijo que extiende a otro de la lista ("casas" tras "casa") no
+    /// añade direcciones nuevas, así que solo cuentan los prefijos sin otro
+    /// más corto de la lista al principio; el resto de eventos son disjuntos.
+    fn probability(&self) -> f64 {
+        prefix_set_probability(&self.prefixes().collect())
+    }
+}
+
+/// Probabilidad de que una dirección empiece por alguno de los prefijos
+///
+/// Solo cuentan los prefijos sin otro más corto del conjunto al principio
+/// (ver `PrefixMatcher::probability`); esos eventos son disjuntos.
+pub fn prefix_set_probability(prefixes: &HashSet<&str>) -> f64 {
+    prefixes
+        .iter()
+        .filter(|prefix| !(1..prefix.len()).any(|len| prefixes.contains(&prefix[..len])))
+        .map(|prefix| 1.0 / 32f64.powi(prefix.len() as i32))
+        .sum::<f64>()
+        .min(1.0)
+}
+
+/// Matcher de diccionario: acepta direcciones que empiezan por cualquier palabra
+///
+/// Las palabras ya normalizadas a base32 se buscan como prefijos (con trie si
+/// son muchas); la etiqueta es la forma normalizada de la palabra más larga que
+/// coincide y `dictionary_word` devuelve su escritura original.
+#[derive(Debug, Clone)]
+pub struct DictionaryMatcher {
+    /// Nombre descriptivo
+    name: String,
+    /// Palabras normalizadas como prefijos
+    prefixes: PrefixMatcher,
+    /// Forma normalizada → palabra original
+    originals: HashMap<String, String>,
+}
+
+impl DictionaryMatcher {
+    /// Crea el matcher a partir de palabras normalizadas
+    pub fn new(words: &[DictionaryWord], min_length: usize) -> Result<Self, String> {
+        if words.is_empty() {
+            return Err("El diccionario no contiene palabras válidas".to_string());
+        }
+
+        let normalized: Vec<String> = words.iter().map(|w| w.normalized.clone()).collect();
+        Ok(Self {
+            name: format!(
+                "diccionario ({} palabras de {}+ caracteres)",
+                words.len(),
+                min_length
+            ),
+            prefixes: PrefixMatcher::new(&normalized)?,
+            originals: words
+                .iter()
+                .map(|w| (w.normalized.clone(), w.original.clone()))
+                .collect(),
+        })
+    }
+}
+
+impl Matcher for DictionaryMatcher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, candidate: &Candidate) -> Option<String> {
+        self.prefixes.matches(candidate)
+    }
+
+    fn probability(&self) -> f64 {
+        self.prefixes.probability()
+    }
+
+    fn dictionary_word(&self, label: &str) -> Option<String> {
+        self.originals.get(label).cloned()
+    }
+}
+
+/// Matcher de sufijos (final de la dirección, antes de ".onion")
+///
+/// Los últimos caracteres salen del checksum SHA3 y del byte de versión, así
+/// que cada candidato necesita calcular el checksum; se hace sobre el payload
+/// binario, sin construir la dirección. La etiqueta de un acierto es el sufijo
+/// más largo que coincide precedido de `*` (por ejemplo `*yd`).
+#[derive(Debug, Clone)]
+pub struct SuffixMatcher {
+    /// Nombre descriptivo
+    name: String,
+    /// Sufijos con sus valores de 5 bits ya decodificados
+    suffixes: Vec<(String, Vec<u8>)>,
+}
+
+impl SuffixMatcher {
+    /// Crea el matcher; falla si algún sufijo es imposible en una dirección v3
+    pub fn new(suffixes: &[String]) -> Result<Self, String> {
+        let mut decoded = Vec::with_capacity(suffixes.len());
+
+        for suffix in suffixes {
+            if let Some(reason) = explain_impossible_suffix(suffix) {
+                return Err(format!("Sufijo '{}' imposible: {}", suffix, reason));
+            }
+            let symbols = suffix.chars().filter_map(base32_value).collect();
+            decoded.push((suffix.clone(), symbols));
+        }
+        // Los más largos primero: el primero que coincide es la etiqueta
+        decoded.sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+
+        Ok(Self {
+            name: format!("sufijo {}", suffixes.join(", ")),
+            suffixes: decoded,
+        })
+    }
+}
+
+impl Matcher for SuffixMatcher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, candidate: &Candidate) -> Option<String> {
+        let payload = onion_payload(candidate.public_key());
+
+        self.suffixes
+            .iter()
+            .find(|(_, symbols)| {
+                let start = ONION_BODY_CHARS - symbols.len();
+                symbols
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .all(|(i, symbol)| payload_symbol(&payload, start + i) == *symbol)
+            })
+            .map(|(suffix, _)| format!("*{}", suffix))
+    }
+
+    /// Como en `prefix_set_probability`, solo cuentan los sufijos que no
+    /// terminan en otro más corto del conjunto; esos eventos son disjuntos.
+    fn probability(&self) -> f64 {
+        let suffixes: HashSet<&str> = self
+            .suffixes
+            .iter()
+            .map(|(suffix, _)| suffix.as_str())
+            .collect();
+        suffixes
+            .iter()
+            .filter(|suffix| !(1..suffix.len()).any(|start| suffixes.contains(&suffix[start..])))
+            .map(|suffix| suffix_probability(suffix))
+            .sum::<f64>()
+            .min(1.0)
+    }
+}
+
+/// Matcher de expresiones regulares sobre la dirección base32
+///
+/// El patrón se evalúa contra los 56 caracteres de la dirección (sin
+/// ".onion"). Al construirlo se recorre su DFA posición a posición, usando
+/// solo los caracteres que el formato v3 permite en cada una, para calcular
+/// la probabilidad exacta de coincidencia; los patrones con probabilidad cero
+/// (caracteres fuera de a-z2-7, finales distintos de 'd', ...) se rechazan.
+/// La etiqueta de un acierto es el patrón entre barras (por ejemplo `/^shop/`).
+#[derive(Debug, Clone)]
+pub struct RegexMatcher {
+    /// Nombre descriptivo
+    name: String,
+    /// Patrón original
+    pattern: String,
+    /// Expresión compilada
+    regex: Regex,
+    /// Probabilidad de coincidencia para una dirección aleatoria
+    probability: f64,
+}
+
+impl RegexMatcher {
+    /// Compila el patrón y comprueba que puede coincidir con alguna dirección
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| format!("Expresión regular '{}' inválida: {}", pattern, e))?;
+        let dfa = dense::DFA::new(pattern)
+            .map_err(|e| format!("Expresión regular '{}' no soportada: {}", pattern, e))?;
+
+        let probability = regex_probability(&dfa)
+            .map_err(|e| format!("Expresión regular '{}' no soportada: {}", pattern, e))?;
+        if probability <= 0.0 {
+            return Err(format!(
+                "La expresión regular '{}' nunca puede coincidir con una dirección v3 \
+                 (solo hay 56 caracteres a-z y 2-7, y la dirección siempre termina en 'd')",
+                pattern
+            ));
+        }
+
+        Ok(Self {
+            name: format!("regex /{}/", pattern),
+            pattern: pattern.to_string(),
+            regex,
+            probability,
+        })
+    }
+}
+
+impl Matcher for RegexMatcher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, candidate: &Candidate) -> Option<String> {
+        self.regex
+            .is_match(candidate.address_body())
+            .then(|| format!("/{}/", self.pattern))
+    }
+
+    fn probability(&self) -> f64 {
+        self.probability
+    }
+}
+
+/// Probabilidad de que el DFA encuentre una coincidencia en una dirección
+/// aleatoria
+///
+/// Propaga la distribución de probabilidad sobre los estados del DFA carácter
+/// a carácter. En cada posición los símbolos posibles son los que dejan los
+/// bits fijos del formato, todos equiprobables. Los estados de coincidencia
+/// son absorbentes: su masa se acumula en el resultado.
+fn regex_probability(dfa: &dense::DFA<Vec<u32>>) -> Result<f64, String> {
+    let start_config = start::Config::new().anchored(Anchored::No);
+    let start = dfa.start_state(&start_config).map_err(|e| e.to_string())?;
+
+    let mut matched = 0.0;
+    let mut states: HashMap<StateID, f64> = HashMap::from([(start, 1.0)]);
+
+    for index in 0..ONION_BODY_CHARS {
+        let (mask, value) = fixed_symbol_bits(index);
+        let symbols: Vec<u8> = BASE32_ALPHABET
+            .iter()
+            .enumerate()
+            .filter(|(symbol, _)| *symbol as u8 & mask == value)
+            .map(|(_, &ch)| ch)
+            .collect();
+        let weight = 1.0 / symbols.len() as f64;
+
+        let mut next: HashMap<StateID, f64> = HashMap::new();
+        for (&state, &mass) in &states {
+            for &ch in &symbols {
+                let target = dfa.next_state(state, ch);
+                if dfa.is_match_state(target) {
+                    matched += mass * weight;
+                } else if dfa.is_quit_state(target) {
+                    return Err("el patrón requiere soporte Unicode".to_string());
+                } else if !dfa.is_dead_state(target) {
+                    *next.entry(target).or_insert(0.0) += mass * weight;
+                }
+            }
+        }
+        states = next;
+    }
+
+    // Coincidencias que terminan justo al final de la dirección
+    for (&state, &mass) in &states {
+        if dfa.is_match_state(dfa.next_eoi_state(state)) {
+            matched += mass;
+        }
+    }
+
+    Ok(matched.min(1.0))
+}
+
+/// Combinación de matchers: acepta el candidato si cumple cualquiera de ellos
+///
+/// Sin matchers no acepta ningún candidato.
+#[derive(Debug, Clone)]
+pub struct AnyMatcher {
+    /// Nombre descriptivo
+    name: String,
+    /// Matchers combinados, en orden de evaluación
+    matchers: Vec<Arc<dyn Matcher>>,
+}
+
+impl AnyMatcher {
+    /// Combina varios matchers
+    pub fn new(matchers: Vec<Arc<dyn Matcher>>) -> Self {
+        let name = matchers
+            .iter()
+            .map(|matcher| matcher.name())
+            .collect::<Vec<_>>()
+            .join(" o ");
+        Self { name, matchers }
+    }
+}
+
+impl Matcher for AnyMatcher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, candidate: &Candidate) -> Option<String> {
+        self.matchers
+            .iter()
+            .find_map(|matcher| matcher.matches(candidate))
+    }
+
+    fn dictionary_word(&self, label: &str) -> Option<String> {
+        self.matchers
+            .iter()
+            .find_map(|matcher| matcher.dictionary_word(label))
+    }
+
+    fn without_labels(&self, labels: &HashSet<String>) -> Option<Arc<dyn Matcher>> {
+        let pruned: Vec<Option<Arc<dyn Matcher>>> = self
+            .matchers
+            .iter()
+            .map(|matcher| matcher.without_labels(labels))
+            .collect();
+        if pruned.iter().all(Option::is_none) {
+            return None;
+        }
+
+        let matchers = pruned
+            .into_iter()
+            .zip(&self.matchers)
+            .map(|(pruned, original)| pruned.unwrap_or_else(|| Arc::clone(original)))
+            .collect();
+        Some(Arc::new(AnyMatcher::new(matchers)))
+    }
+
+    /// Probabilidad de la unión, 1 - ∏(1 - pᵢ), suponiendo hijos independientes
+    ///
+    /// Es exacta para reglas sobre partes distintas de la dirección (prefijo y
+    /// sufijo). Si los hijos se solapan (una regex que también acepta un
+    /// prefijo de otro hijo) la unión real es menor: el valor queda entre el
+    /// mayor pᵢ y la suma, así que sobreestima algo la probabilidad.
+    fn probability(&self) -> f64 {
+        1.0 - self
+            .matchers
+            .iter()
+            .map(|matcher| 1.0 - matcher.probability())
+            .product::<f64>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_address() {
+        let public_key = [0u8; 32];
+        let candidate = Candidate::new(&public_key);
+
+        assert_eq!(candidate.address(), generate_onion_address(&public_key));
+        assert_eq!(candidate.address_body().len(), 56);
+        assert_eq!(
+            candidate.into_address(),
+            generate_onion_address(&public_key)
+        );
+    }
+
+    #[test]
+    fn test_prefix_matcher() {
+        let public_key = [0u8; 32]; // Dirección "aaaa..."
+        let matcher = PrefixMatcher::new(&["test".to_string(), "aa".to_string()]).unwrap();
+
+        let candidate = Candidate::new(&public_key);
+        assert_eq!(matcher.matches(&candidate), Some("aa".to_string()));
+        assert_eq!(matcher.prefixes().collect::<Vec<_>>(), vec!["test", "aa"]);
+
+        let no_match = PrefixMatcher::new(&["test".to_string()]).unwrap();
+        assert_eq!(no_match.matches(&Candidate::new(&public_key)), None);
+
+        assert!(PrefixMatcher::new(&["test1".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_prefix_matcher_trie() {
+        let public_key = [0u8; 32]; // Dirección "aaaa..."
+        let mut prefixes: Vec<String> = (0..PrefixMatcher::TRIE_THRESHOLD)
+            .map(|i| format!("b{}", BASE32_ALPHABET[i] as char))
+            .collect();
+        prefixes.push("aaa".to_string());
+        prefixes.push("aaaaa".to_string());
+
+        let matcher = PrefixMatcher::new(&prefixes).unwrap();
+        assert!(matches!(matcher.index, PrefixIndex::Trie(_)));
+        assert!(matcher.name().starts_with("18 prefijos"));
+
+        // Se informa el prefijo más largo que coincide
+        let candidate = Candidate::new(&public_key);
+        assert_eq!(matcher.matches(&candidate), Some("aaaaa".to_string()));
+
+        // "aaaaa" está contenido en "aaa" y no suma probabilidad
+        let expected = 16.0 / 1024.0 + 1.0 / 32768.0;
+        assert!((matcher.probability() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_prefix_matcher_without_labels() {
+        let public_key = [0u8; 32]; // Dirección "aaaa..."
+        let prefixes = vec!["aaa".to_string(), "aa".to_string(), "zz".to_string()];
+        let matcher = PrefixMatcher::new(&prefixes).unwrap();
+        assert_eq!(
+            matcher.matches(&Candidate::new(&public_key)),
+            Some("aaa".to_string())
+        );
+
+        // Con "aaa" cubierto, el mismo candidato se etiqueta con "aa"
+        let filled: HashSet<String> = ["aaa".to_string()].into();
+        let pruned = matcher.without_labels(&filled).unwrap();
+        assert_eq!(
+            pruned.matches(&Candidate::new(&public_key)),
+            Some("aa".to_string())
+        );
+
+        let filled: HashSet<String> = prefixes.iter().cloned().collect();
+        let pruned = matcher.without_labels(&filled).unwrap();
+        assert_eq!(pruned.matches(&Candidate::new(&public_key)), None);
+        assert_eq!(pruned.probability(), 0.0);
+    }
+
+    #[test]
+    fn test_prefix_matcher_probability() {
+        let matcher = PrefixMatcher::new(&["a".to_string(), "bc".to_string()]).unwrap();
+        let expected = 1.0 / 32.0 + 1.0 / 1024.0;
+        assert!((matcher.probability() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_suffix_matcher() {
+        let public_key = [0u8; 32];
+        let address = generate_onion_address(&public_key);
+        let tail = &address[ONION_BODY_CHARS - 3..ONION_BODY_CHARS];
+
+        let matcher = SuffixMatcher::new(&["yd".to_string(), tail.to_string()]).unwrap();
+        assert_eq!(
+            matcher.matches(&Candidate::new(&public_key)),
+            Some(format!("*{}", tail))
+        );
+
+        // Con sufijos anidados, la etiqueta es el más largo que coincide
+        let matcher = SuffixMatcher::new(&[tail[1..].to_string(), tail.to_string()]).unwrap();
+        assert_eq!(
+            matcher.matches(&Candidate::new(&public_key)),
+            Some(format!("*{}", tail))
+        );
+
+        // "ayd" termina en "yd": no suma probabilidad
+        let matcher = SuffixMatcher::new(&["yd".to_string(), "ayd".to_string()]).unwrap();
+        assert_eq!(matcher.probability(), 0.25);
+        let matcher = SuffixMatcher::new(&["ad".to_string(), "qd".to_string()]).unwrap();
+        assert_eq!(matcher.probability(), 2.0 * suffix_probability("ad"));
+
+        assert!(SuffixMatcher::new(&["shop".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_dictionary_matcher() {
+        let public_key = [0u8; 32]; // Dirección "aaaa..."
+        let words = crate::dictionary::normalize_words(["aáaa", "áaaaa", "casa", "casas"], 4);
+        let matcher = DictionaryMatcher::new(&words, 4).unwrap();
+
+        let label = matcher.matches(&Candidate::new(&public_key)).unwrap();
+        assert_eq!(label, "aaaaa");
+        assert_eq!(matcher.dictionary_word(&label), Some("áaaaa".to_string()));
+
+        // "aaaaa" y "casas" amplían a "aaaa" y "casa"
+        assert!((matcher.probability() - 2.0 / 32f64.powi(4)).abs() < 1e-15);
+        assert!(DictionaryMatcher::new(&[], 4).is_err());
+    }
+
+    #[test]
+    fn test_any_matcher() {
+        let public_key = [0u8; 32]; // Dirección "aaaa..."
+        let prefix: Arc<dyn Matcher> = Arc::new(PrefixMatcher::new(&["zz".to_string()]).unwrap());
+        let suffix: Arc<dyn Matcher> = Arc::new(SuffixMatcher::new(&["d".to_string()]).unwrap());
+
+        let matcher = AnyMatcher::new(vec![prefix, suffix]);
+        assert_eq!(matcher.name(), "prefijo zz o sufijo d");
+        assert_eq!(
+            matcher.matches(&Candidate::new(&public_key)),
+            Some("*d".to_string())
+        );
+        assert_eq!(matcher.probability(), 1.0);
+    }
+
+    #[test]
+    fn test_any_matcher_probability() {
+        let prefix: Arc<dyn Matcher> = Arc::new(PrefixMatcher::new(&["ab".to_string()]).unwrap());
+        let suffix: Arc<dyn Matcher> = Arc::new(SuffixMatcher::new(&["yd".to_string()]).unwrap());
+        let regex: Arc<dyn Matcher> = Arc::new(RegexMatcher::new("^a").unwrap());
+
+        // Prefijo y sufijo son independientes: la unión es exacta
+        let matcher = AnyMatcher::new(vec![Arc::clone(&prefix), suffix]);
+        let expected = 1.0 - (1.0 - 1.0 / 1024.0) * 0.75;
+        assert!((matcher.probability() - expected).abs() < 1e-15);
+
+        // La regex acepta todo lo que acepta el prefijo: la unión real es 1/32.
+        // La estimación queda entre el mayor hijo y la suma, no por encima
+        let matcher = AnyMatcher::new(vec![prefix, regex]);
+        let probability = matcher.probability();
+        assert!(probability >= 1.0 / 32.0);
+        assert!(probability < 1.0 / 32.0 + 1.0 / 1024.0);
+
+        assert_eq!(AnyMatcher::new(Vec::new()).probability(), 0.0);
+    }
+
+    #[test]
+    fn test_regex_matcher() {
+        let public_key = [0u8; 32]; // Dirección "aaaa..."
+        let matcher = RegexMatcher::new("^a{4}[a-z]").unwrap();
+        assert_eq!(
+            matcher.matches(&Candidate::new(&public_key)),
+            Some("/^a{4}[a-z]/".to_string())
+        );
+        assert_eq!(matcher.name(), "regex /^a{4}[a-z]/");
+
+        let no_match = RegexMatcher::new("^(shop|store)[a-z]{2}").unwrap();
+        assert_eq!(no_match.matches(&Candidate::new(&public_key)), None);
+    }
+
+    #[test]
+    fn test_regex_matcher_probability() {
+        // Prefijo literal: igual que un prefijo normal
+        let matcher = RegexMatcher::new("^shop").unwrap();
+        assert!((matcher.probability() - 1.0 / 32f64.powi(4)).abs() < 1e-15);
+
+        // Alternativa anclada: suma de probabilidades
+        let matcher = RegexMatcher::new("^(shop|store)").unwrap();
+        let expected = 1.0 / 32f64.powi(4) + 1.0 / 32f64.powi(5);
+        assert!((matcher.probability() - expected).abs() < 1e-15);
+
+        // Final fijo: siempre termina en 'd', penúltimo a/i/q/y
+        assert_eq!(RegexMatcher::new("d$").unwrap().probability(), 1.0);
+        assert_eq!(RegexMatcher::new("[ai]d$").unwrap().probability(), 0.5);
+    }
+
+    #[test]
+    fn test_regex_matcher_rejects_impossible() {
+        assert!(RegexMatcher::new("^shop1").is_err()); // '1' no es base32
+        assert!(RegexMatcher::new("[A-Z]").is_err()); // Mayúsculas
+        assert!(RegexMatcher::new("e$").is_err()); // Siempre termina en 'd'
+        assert!(RegexMatcher::new("^[a-z]{57}").is_err()); // Demasiado largo
+        assert!(RegexMatcher::new("(unclosed").is_err());
+    }
+}
@@ -0,0 +1,405 @@
+use crate::search::{
+    estimate_time_for_probability, format_duration, format_eta_percentiles, time_for_confidence,
+    ETA_CONFIDENCES,
+};
+use crate::types::{Config, SearchStats, StopReason, VanityResult};
+use crossbeam::channel::{bounded, RecvTimeoutError, Sender};
+use std::fmt;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Número máximo de prefijos con estimación propia en el informe
+const LISTED_PREFIXES: usize = 5;
+
+/// Formato del informe de progreso
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportStyle {
+    /// Bloque de varias líneas con estimaciones por prefijo
+    Full,
+    /// Una sola línea que se redibuja en el sitio (en stderr)
+    Compact,
+    /// No imprime nada: solo avisa al `SearchObserver` de la configuración
+    Silent,
+}
+
+/// Observador de los eventos de una búsqueda
+///
+/// `search_vanity` avisa al observador de la configuración (si lo hay) de cada
+/// informe de progreso, de cada resultado aceptado y del final de la búsqueda.
+/// Los informes llegan desde el hilo del informe, por eso debe ser `Sync`.
+pub trait SearchObserver: Send + Sync + fmt::Debug {
+    /// Informe periódico, al intervalo de `Config::report`
+    fn on_progress(&self, _report: &ProgressReport) {}
+
+    /// Resultado aceptado, antes de entregarlo al callback
+    fn on_result(&self, _result: &VanityResult) {}
+
+    /// Estadísticas finales, también tras cancelar o agotar los límites
+    fn on_finish(&self, _stats: &SearchStats) {}
+}
+
+/// Cada cuánto y cómo informar del progreso durante la búsqueda
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportOptions {
+    /// Intervalo entre informes
+    pub interval: Duration,
+    /// Formato del informe
+    pub style: ReportStyle,
+}
+
+/// Estimación de un patrón en un momento de la búsqueda
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternEstimate {
+    /// Patrón (un prefijo o el nombre del matcher)
+    pub label: String,
+    /// Probabilidad de que un intento coincida
+    pub probability: f64,
+    /// Tiempo esperado hasta el siguiente acierto a la tasa actual (segundos)
+    pub eta_seconds: f64,
+    /// Tiempo hasta cada probabilidad de `ETA_CONFIDENCES` a la tasa actual (segundos)
+    pub eta_percentiles: [f64; 3],
+    /// Probabilidad de haber encontrado ya al menos un acierto con los intentos hechos
+    pub chance_so_far: f64,
+}
+
+/// Estado de la búsqueda que muestra cada informe
+#[derive(Debug, Clone)]
+pub struct ProgressReport {
+    /// Estadísticas acumuladas
+    pub stats: SearchStats,
+    /// Tasa del último intervalo (intentos por segundo)
+    pub current_rate: f64,
+    /// Estimaciones: primero los prefijos listados y al final el matcher completo
+    pub patterns: Vec<PatternEstimate>,
+    /// Factor de suerte de la búsqueda (ver `luck_factor`)
+    pub luck: Option<f64>,
+}
+
+impl ProgressReport {
+    /// Calcula las estimaciones del informe
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuración de la búsqueda
+    /// * `stats` - Estadísticas acumuladas hasta ahora
+    /// * `current_rate` - Tasa del último intervalo
+    ///
+    /// # Returns
+    ///
+    /// El informe, sin estimaciones si el matcher no tiene una probabilidad útil
+    pub fn new(config: &Config, stats: SearchStats, current_rate: f64) -> Self {
+        let estimate = |label: String, probability: f64| PatternEstimate {
+            label,
+            probability,
+            eta_seconds: estimate_time_for_probability(probability, current_rate),
+            eta_percentiles: ETA_CONFIDENCES
+                .map(|confidence| time_for_confidence(probability, confidence, current_rate)),
+            chance_so_far: chance_so_far(probability, stats.total_attempts),
+        };
+
+        let mut patterns = Vec::new();
+        let probability = config.matcher.probability();
+        let luck = luck_factor(probability, stats.total_attempts, stats.results_found);
+        if probability < 1.0 {
+            if (2..=LISTED_PREFIXES).contains(&config.prefixes.len()) {
+                for prefix in &config.prefixes {
+                    let probability = 1.0 / 32f64.powi(prefix.len() as i32);
+                    patterns.push(estimate(format!("'{}'", prefix), probability));
+                }
+            }
+            patterns.push(estimate(config.matcher.name().to_string(), probability));
+        }
+
+        Self {
+            stats,
+            current_rate,
+            patterns,
+            luck,
+        }
+    }
+
+    /// Informe completo, una línea por estimación
+    pub fn format_full(&self) -> String {
+        let mut text = format!(
+            "[STATS] Intentos: {} | Encontrados: {} | Tasa: {:.2} intentos/s (media {:.2}) | Tiempo: {}",
+            self.stats.total_attempts,
+            self.stats.results_found,
+            self.current_rate,
+            self.stats.rate(),
+            format_duration(self.stats.elapsed_seconds)
+        );
+        if let Some(luck) = self.luck {
+            text.push_str(&format!(" | Suerte: {:.2}x", luck));
+        }
+        for pattern in &self.patterns {
+            text.push_str(&format!(
+                "\n        • {}: ETA ~{} ({}) | probabilidad hasta ahora: {:.1}%",
+                pattern.label,
+                format_duration(pattern.eta_seconds),
+                format_eta_percentiles(pattern.probability, self.current_rate),
+                pattern.chance_so_far * 100.0
+            ));
+        }
+        text
+    }
+
+    /// Informe de una línea con la estimación del matcher completo
+    pub fn format_compact(&self) -> String {
+        let mut text = format!(
+            "⏳ {} intentos | {:.0}/s | {} | {} encontrados",
+            self.stats.total_attempts,
+            self.current_rate,
+            format_duration(self.stats.elapsed_seconds),
+            self.stats.results_found
+        );
+        if let Some(pattern) = self.patterns.last() {
+            text.push_str(&format!(
+                " | ETA ~{} ({:.0}%)",
+                format_duration(pattern.eta_seconds),
+                pattern.chance_so_far * 100.0
+            ));
+        }
+        text
+    }
+}
+
+/// Probabilidad de al menos un acierto en `attempts` intentos: 1 - (1 - p)^n
+///
+/// Se calcula con `ln_1p`/`exp_m1` para no perder precisión con p diminutas.
+pub fn chance_so_far(probability: f64, attempts: u64) -> f64 {
+    if probability <= 0.0 {
+        return 0.0;
+    }
+    if probability >= 1.0 {
+        return if attempts > 0 { 1.0 } else { 0.0 };
+    }
+    -(attempts as f64 * (-probability).ln_1p()).exp_m1()
+}
+
+/// Factor de suerte: intentos esperados para los resultados obtenidos entre
+/// los intentos realizados
+///
+/// Mayor que 1 significa que se ha encontrado antes de lo esperado.
+///
+/// # Returns
+///
+/// None sin resultados (la suerte se mide entonces con `chance_so_far`)
+pub fn luck_factor(probability: f64, attempts: u64, found: usize) -> Option<f64> {
+    if probability <= 0.0 || attempts == 0 || found == 0 {
+        return None;
+    }
+    Some(found as f64 / probability / attempts as f64)
+}
+
+/// Líneas del resumen final sobre la suerte de una búsqueda
+///
+/// Con resultados, el factor de suerte; sin ellos, la probabilidad que había
+/// de encontrarlo ya y el tiempo restante por percentiles a la tasa media.
+///
+/// # Arguments
+///
+/// * `probability` - Probabilidad de que un intento coincida
+/// * `attempts` - Intentos realizados
+/// * `found` - Resultados encontrados
+/// * `rate` - Tasa media (intentos por segundo)
+pub fn luck_summary(probability: f64, attempts: u64, found: usize, rate: f64) -> Vec<String> {
+    if probability <= 0.0 || probability >= 1.0 {
+        return Vec::new();
+    }
+
+    match luck_factor(probability, attempts, found) {
+        Some(luck) => vec![format!(
+            "Suerte: {:.2}x ({} de lo esperado: {:.0}% de los intentos medios)",
+            luck,
+            if luck >= 1.0 { "mejor" } else { "peor" },
+            100.0 / luck
+        )],
+        None => vec![
+            format!(
+                "Probabilidad de haberlo encontrado ya: {:.1}%",
+                chance_so_far(probability, attempts) * 100.0
+            ),
+            format!("Tiempo restante: {}", format_eta_percentiles(probability, rate)),
+        ],
+    }
+}
+
+/// Borra la línea del informe compacto para escribir otra cosa encima
+pub fn clear_compact_line() {
+    eprint!("\r\x1b[K");
+}
+
+/// Hilo que informa del progreso mientras dura la búsqueda
+pub struct Reporter {
+    /// Al soltarse, el hilo termina sin esperar al siguiente intervalo
+    stop: Sender<()>,
+    /// Hilo del informe
+    handle: JoinHandle<()>,
+}
+
+impl Reporter {
+    /// Lanza el hilo del informe
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuración de la búsqueda (patrones y formato)
+    /// * `options` - Intervalo y formato de los informes
+    /// * `attempts` - Contador de intentos compartido con los workers
+    /// * `found` - Contador de resultados aceptados (sin los descartados por
+    ///   cupos cubiertos)
+    /// * `previous_seconds` - Tiempo de sesiones anteriores (al reanudar)
+    pub fn spawn(
+        config: &Config,
+        options: ReportOptions,
+        attempts: Arc<AtomicU64>,
+        found: Arc<AtomicU64>,
+        previous_seconds: f64,
+    ) -> Self {
+        let (stop, stopped) = bounded::<()>(0);
+        let config = config.clone();
+        let start_time = Instant::now();
+
+        let handle = std::thread::spawn(move || {
+            let mut last_attempts = attempts.load(Ordering::Relaxed);
+            let mut last_time = start_time;
+
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(options.interval) {
+                let now = Instant::now();
+                let total_attempts = attempts.load(Ordering::Relaxed);
+                let interval = now.duration_since(last_time).as_secs_f64();
+                let current_rate = (total_attempts - last_attempts) as f64 / interval;
+                last_attempts = total_attempts;
+                last_time = now;
+
+                let stats = SearchStats {
+                    total_attempts,
+                    results_found: found.load(Ordering::Relaxed) as usize,
+                    elapsed_seconds: previous_seconds + now.duration_since(start_time).as_secs_f64(),
+                    patterns: Vec::new(),
+                    stop_reason: StopReason::GoalReached,
+                };
+                let report = ProgressReport::new(&config, stats, current_rate);
+                match options.style {
+                    ReportStyle::Full => println!("{}", report.format_full()),
+                    ReportStyle::Compact => {
+                        clear_compact_line();
+                        eprint!("{}", report.format_compact());
+                        let _ = std::io::stderr().flush();
+                    }
+                    ReportStyle::Silent => {}
+                }
+                if let Some(observer) = &config.observer {
+                    observer.on_progress(&report);
+                }
+            }
+
+            if options.style == ReportStyle::Compact {
+                clear_compact_line();
+            }
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Detiene el hilo y espera a que termine
+    pub fn finish(self) {
+        drop(self.stop);
+        let _ = self.handle.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(prefixes: &[&str]) -> Config {
+        Config {
+            threads: 1,
+            ..Config::for_test(prefixes)
+        }
+    }
+
+    #[test]
+    fn test_chance_so_far() {
+        assert_eq!(chance_so_far(0.5, 0), 0.0);
+        assert!((chance_so_far(0.5, 2) - 0.75).abs() < 1e-12);
+        assert_eq!(chance_so_far(0.0, 1000), 0.0);
+
+        // Con el número esperado de intentos, ~63% (1 - 1/e)
+        let p = 1.0 / 32f64.powi(8);
+        assert!((chance_so_far(p, 32u64.pow(8)) - 0.632).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_luck() {
+        assert_eq!(luck_factor(0.5, 0, 0), None);
+        assert_eq!(luck_factor(0.5, 10, 0), None);
+        assert_eq!(luck_factor(1.0 / 1024.0, 512, 1), Some(2.0));
+        assert_eq!(luck_factor(1.0 / 1024.0, 4096, 2), Some(0.5));
+
+        let lines = luck_summary(1.0 / 1024.0, 512, 1, 1024.0);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("Suerte: 2.00x (mejor"), "{:?}", lines);
+
+        let lines = luck_summary(1.0 / 1024.0, 1024, 0, 1024.0);
+        assert_eq!(lines[0], "Probabilidad de haberlo encontrado ya: 63.2%");
+        assert!(lines[1].contains("90%: ~2s"), "{:?}", lines);
+
+        assert!(luck_summary(0.0, 10, 0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_progress_report() {
+        let stats = SearchStats {
+            total_attempts: 32 * 32,
+            results_found: 0,
+            elapsed_seconds: 2.0,
+            patterns: Vec::new(),
+            stop_reason: StopReason::GoalReached,
+        };
+
+        // Un prefijo: solo la línea del matcher
+        let report = ProgressReport::new(&config(&["ab"]), stats.clone(), 1024.0);
+        assert_eq!(report.patterns.len(), 1);
+        assert!((report.patterns[0].eta_seconds - 1.0).abs() < 1e-9);
+        let full = report.format_full();
+        assert!(full.starts_with("[STATS] Intentos: 1024"), "{}", full);
+        assert!(full.contains("prefijo ab: ETA ~1s (50%: ~0s | 90%: ~2s | 99%: ~4s)"), "{}", full);
+        assert!(!full.contains("Suerte"), "{}", full);
+        assert_eq!(report.patterns[0].eta_percentiles.map(|t| t.round()), [1.0, 2.0, 5.0]);
+
+        // Varios prefijos: uno por línea y el total al final
+        let report = ProgressReport::new(&config(&["ab", "abc"]), stats, 1024.0);
+        let labels: Vec<&str> = report.patterns.iter().map(|p| p.label.as_str()).collect();
+        assert_eq!(labels, vec!["'ab'", "'abc'", "prefijo ab, abc"]);
+        assert!(report.patterns[1].chance_so_far < report.patterns[0].chance_so_far);
+
+        let lucky = SearchStats {
+            results_found: 1,
+            ..report.stats.clone()
+        };
+        let full = ProgressReport::new(&config(&["ab"]), lucky, 1024.0).format_full();
+        assert!(full.contains("| Suerte: 1.00x"), "{}", full);
+
+        let compact = report.format_compact();
+        assert!(!compact.contains('\n'));
+        assert!(compact.contains("1024 intentos"), "{}", compact);
+    }
+
+    #[test]
+    fn test_reporter_stops_promptly() {
+        let options = ReportOptions {
+            interval: Duration::from_secs(3600),
+            style: ReportStyle::Full,
+        };
+        let attempts = Arc::new(AtomicU64::new(0));
+        let found = Arc::new(AtomicU64::new(0));
+
+        let start = Instant::now();
+        Reporter::spawn(&config(&["ab"]), options, attempts, found, 0.0).finish();
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,338 @@
+use crate::error::{Result, VanityError};
+use crate::matcher::{Candidate, Matcher};
+use crate::search::search_vanity;
+use crate::types::{Config, SearchStats, VanityResult};
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Criterio para puntuar direcciones en el modo top-N
+///
+/// A diferencia de un `Matcher`, un scorer no decide si una dirección vale o
+/// no: le da una puntuación y la búsqueda se queda con las N mejores.
+pub trait Scorer: Send + Sync + fmt::Debug {
+    /// Nombre descriptivo del criterio
+    fn name(&self) -> &str;
+
+    /// Puntuación de un candidato (más alta = mejor)
+    fn score(&self, candidate: &Candidate) -> u32;
+
+    /// Etiqueta que se guarda como `matched_prefix` de un resultado
+    fn label(&self, _candidate: &Candidate, score: u32) -> String {
+        format!("{}={}", self.name(), score)
+    }
+}
+
+/// Puntúa por el número de caracteres iniciales que coinciden con algún objetivo
+///
+/// Sirve para acercarse todo lo posible a un prefijo demasiado largo para
+/// encontrarlo completo: "torgeforge" puede dar "torgef..." en un tiempo
+/// razonable.
+#[derive(Debug, Clone)]
+pub struct LongestPrefixScorer {
+    /// Prefijos objetivo
+    targets: Vec<String>,
+}
+
+impl LongestPrefixScorer {
+    /// Crea el scorer con los prefijos objetivo
+    pub fn new(targets: &[String]) -> Result<Self> {
+        if targets.is_empty() {
+            return Err(VanityError::InvalidConfig(
+                "El criterio de prefijo más largo necesita al menos un prefijo".to_string(),
+            ));
+        }
+        Ok(Self {
+            targets: targets.to_vec(),
+        })
+    }
+}
+
+impl Scorer for LongestPrefixScorer {
+    fn name(&self) -> &str {
+        "prefijo más largo"
+    }
+
+    fn score(&self, candidate: &Candidate) -> u32 {
+        let body = candidate.address_body().as_bytes();
+        self.targets
+            .iter()
+            .map(|target| {
+                target
+                    .bytes()
+                    .zip(body)
+                    .take_while(|(expected, actual)| expected == *actual)
+                    .count() as u32
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn label(&self, candidate: &Candidate, score: u32) -> String {
+        candidate.address_body()[..score as usize].to_string()
+    }
+}
+
+/// Puntúa por la longitud del tramo inicial que alterna consonantes y vocales
+///
+/// "bakemono..." puntúa 8; un dígito o dos vocales o consonantes seguidas
+/// cortan el tramo. La 'y' cuenta como vocal.
+#[derive(Debug, Clone, Default)]
+pub struct PronounceableScorer;
+
+impl PronounceableScorer {
+    fn is_vowel(ch: u8) -> bool {
+        matches!(ch, b'a' | b'e' | b'i' | b'o' | b'u' | b'y')
+    }
+
+    /// Longitud del tramo inicial pronunciable de una dirección
+    fn run_length(body: &str) -> u32 {
+        let body = body.as_bytes();
+        if !body.first().is_some_and(u8::is_ascii_lowercase) {
+            return 0;
+        }
+
+        let run = body
+            .windows(2)
+            .take_while(|pair| {
+                pair[1].is_ascii_lowercase() && Self::is_vowel(pair[0]) != Self::is_vowel(pair[1])
+            })
+            .count();
+        run as u32 + 1
+    }
+}
+
+impl Scorer for PronounceableScorer {
+    fn name(&self) -> &str {
+        "pronunciable"
+    }
+
+    fn score(&self, candidate: &Candidate) -> u32 {
+        Self::run_length(candidate.address_body())
+    }
+}
+
+/// Puntúa por el número de letras de la dirección (menos dígitos = mejor)
+#[derive(Debug, Clone, Default)]
+pub struct FewestDigitsScorer;
+
+impl Scorer for FewestDigitsScorer {
+    fn name(&self) -> &str {
+        "menos dígitos"
+    }
+
+    fn score(&self, candidate: &Candidate) -> u32 {
+        candidate
+            .address_body()
+            .bytes()
+            .filter(u8::is_ascii_lowercase)
+            .count() as u32
+    }
+}
+
+/// Matcher que acepta candidatos con puntuación mínima
+///
+/// El umbral sube a medida que se llena el top, de modo que los workers solo
+/// envían candidatos que pueden entrar en él.
+#[derive(Debug)]
+pub struct ScoreMatcher {
+    /// Criterio de puntuación
+    scorer: Arc<dyn Scorer>,
+    /// Puntuación mínima para aceptar un candidato
+    threshold: AtomicU32,
+}
+
+impl ScoreMatcher {
+    /// Crea el matcher con umbral inicial 1 (se descartan las puntuaciones 0)
+    pub fn new(scorer: Arc<dyn Scorer>) -> Self {
+        Self {
+            scorer,
+            threshold: AtomicU32::new(1),
+        }
+    }
+
+    /// Fija la puntuación mínima a partir de la cual se aceptan candidatos
+    pub fn raise_threshold(&self, threshold: u32) {
+        self.threshold.fetch_max(threshold, Ordering::Relaxed);
+    }
+}
+
+impl Matcher for ScoreMatcher {
+    fn name(&self) -> &str {
+        self.scorer.name()
+    }
+
+    fn matches(&self, candidate: &Candidate) -> Option<String> {
+        let score = self.scorer.score(candidate);
+        if score < self.threshold.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(self.scorer.label(candidate, score))
+    }
+
+    /// El modo puntuación termina por presupuesto, no por aciertos: no hay una
+    /// probabilidad útil para estimar tiempos
+    fn probability(&self) -> f64 {
+        1.0
+    }
+}
+
+/// Resultado del modo top-N con su puntuación
+#[derive(Debug, Clone)]
+pub struct ScoredResult {
+    /// Puntuación según el scorer
+    pub score: u32,
+    /// Resultado de la búsqueda
+    pub result: VanityResult,
+}
+
+/// Busca las `top` direcciones mejor puntuadas dentro de un presupuesto
+///
+/// Ejecuta `search_vanity` sin límite de resultados, con el matcher de la
+/// configuración sustituido por un `ScoreMatcher`, hasta agotar `max_attempts`,
+/// `deadline` o `max_time` (al menos uno es obligatorio).
+///
+/// # Arguments
+///
+/// * `config` - Configuración de la búsqueda (su matcher se ignora)
+/// * `scorer` - Criterio de puntuación
+/// * `top` - Número de direcciones a conservar
+/// * `on_improvement` - Se llama cada vez que una dirección entra en el top
+///
+/// # Returns
+///
+/// Las mejores direcciones (de mayor a menor puntuación) junto con el
+/// resultado de la búsqueda; las ganadoras se devuelven aunque la búsqueda
+/// termine con error (por ejemplo, al cancelarla desde `on_improvement`)
+pub fn search_top<F>(
+    config: &Config,
+    scorer: Arc<dyn Scorer>,
+    top: usize,
+    mut on_improvement: F,
+) -> (Vec<ScoredResult>, Result<SearchStats>)
+where
+    F: FnMut(&ScoredResult) -> Result<()>,
+{
+    if top == 0 {
+        let error = VanityError::InvalidConfig("El top debe tener al menos 1 dirección".into());
+        return (Vec::new(), Err(error));
+    }
+    if config.max_attempts.is_none() && config.deadline.is_none() && config.max_time.is_none() {
+        let error = VanityError::InvalidConfig(
+            "El modo puntuación necesita un límite de intentos o de tiempo".to_string(),
+        );
+        return (Vec::new(), Err(error));
+    }
+
+    let matcher = Arc::new(ScoreMatcher::new(Arc::clone(&scorer)));
+    let config = Config {
+        matcher: Arc::clone(&matcher) as Arc<dyn Matcher>,
+        max_results: usize::MAX,
+        quotas: Vec::new(),
+        checkpoint_interval: None,
+        resume: None,
+        ..config.clone()
+    };
+
+    let mut winners: Vec<ScoredResult> = Vec::with_capacity(top + 1);
+    let outcome = search_vanity(&config, |result| {
+        let score = scorer.score(&Candidate::new(&result.public_key));
+
+        // Los workers pueden enviar candidatos con un umbral ya superado
+        if winners.len() == top && winners.last().is_some_and(|worst| score <= worst.score) {
+            return Ok(());
+        }
+
+        // Ante empate se mantiene primero el más antiguo
+        let position = winners.partition_point(|entry| entry.score >= score);
+        winners.insert(position, ScoredResult { score, result });
+        winners.truncate(top);
+
+        if winners.len() == top {
+            if let Some(worst) = winners.last() {
+                matcher.raise_threshold(worst.score + 1);
+            }
+        }
+
+        on_improvement(&winners[position])
+    });
+
+    (winners, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_prefix_scorer() {
+        let candidate = Candidate::new(&[0u8; 32]); // Dirección "aaaa..."
+        let scorer = LongestPrefixScorer::new(&["aab".to_string(), "aaaab".to_string()]).unwrap();
+        assert_eq!(scorer.score(&candidate), 4);
+        assert_eq!(scorer.label(&candidate, 4), "aaaa");
+
+        assert!(LongestPrefixScorer::new(&[]).is_err());
+    }
+
+    #[test]
+    fn test_pronounceable_and_digits_scorers() {
+        assert_eq!(PronounceableScorer::run_length("bakemono2x"), 8);
+        assert_eq!(PronounceableScorer::run_length("yuki"), 1);
+        assert_eq!(PronounceableScorer::run_length("strong"), 1);
+        assert_eq!(PronounceableScorer::run_length("7abc"), 0);
+
+        // "aaaa...": dos vocales seguidas cortan el tramo
+        let candidate = Candidate::new(&[0u8; 32]);
+        assert_eq!(PronounceableScorer.score(&candidate), 1);
+
+        let digits = candidate
+            .address_body()
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .count() as u32;
+        assert_eq!(FewestDigitsScorer.score(&candidate), 56 - digits);
+    }
+
+    #[test]
+    fn test_search_top() {
+        let config = Config {
+            prefixes: Vec::new(),
+            matcher: Arc::new(ScoreMatcher::new(Arc::new(FewestDigitsScorer))),
+            max_attempts: Some(20_000),
+            ..Config::for_test(&["a"])
+        };
+
+        let mut improvements = 0;
+        let (winners, outcome) = search_top(&config, Arc::new(FewestDigitsScorer), 3, |_| {
+            improvements += 1;
+            Ok(())
+        });
+
+        let stats = outcome.unwrap();
+        assert!(stats.total_attempts >= 20_000);
+        assert_eq!(winners.len(), 3);
+        assert!(improvements >= 3);
+        assert!(winners
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+        for winner in &winners {
+            let candidate = Candidate::new(&winner.result.public_key);
+            assert_eq!(FewestDigitsScorer.score(&candidate), winner.score);
+            assert_eq!(candidate.address(), winner.result.address);
+        }
+    }
+
+    #[test]
+    fn test_search_top_requires_budget() {
+        let config = Config {
+            prefixes: Vec::new(),
+            matcher: Arc::new(ScoreMatcher::new(Arc::new(FewestDigitsScorer))),
+            threads: 1,
+            ..Config::for_test(&["a"])
+        };
+
+        let (winners, outcome) = search_top(&config, Arc::new(PronounceableScorer), 1, |_| Ok(()));
+        assert!(winners.is_empty());
+        assert!(matches!(outcome, Err(VanityError::InvalidConfig(_))));
+    }
+}
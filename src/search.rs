@@ -1,28 +1,45 @@
+use crate::checkpoint::Checkpoint;
 use crate::error::{Result, VanityError};
-use crate::generator::{generate_keypair, generate_onion_address, matches_prefix};
-use crate::types::{Config, SearchStats, VanityResult};
-use crossbeam::channel::bounded;
+use crate::generator::BATCH_SIZE;
+use crate::matcher::{Candidate, Matcher};
+use crate::reporter::Reporter;
+use crate::types::{CancelToken, Config, PatternStats, SearchStats, StopReason, VanityResult};
+use crossbeam::channel::{bounded, never, Receiver, RecvTimeoutError};
 use rayon::prelude::*;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 /// Ejecuta la búsqueda de direcciones vanity en paralelo
 /// 
-/// Utiliza rayon para paralelizar la generación de claves y búsqueda.
+/// Utiliza un pool de rayon propio de la búsqueda, con `config.threads` hilos,
+/// de modo que varias búsquedas en el mismo proceso no comparten hilos.
+/// Cada worker recorre el espacio de claves con un `KeyWalker` propio que
+/// le da la `KeySource` configurada, de modo que cada intento cuesta una suma
+/// de puntos en lugar de una generación de clave completa. Los candidatos se
+/// procesan en lotes de `BATCH_SIZE` que comparten una sola inversión de campo.
 /// La búsqueda se detiene cuando:
-/// - Se alcanza max_results
+/// - Se alcanza max_results, o se cubren todos los cupos si hay `quotas` (los
+///   patrones sin cupo comparten entonces max_results y no la retienen)
 /// - Se alcanza max_attempts (si está configurado)
-/// - Se recibe una señal de cancelación
+/// - Se alcanza el deadline o se agota max_time (si están configurados)
+/// - Se cancela `config.cancel`: los workers paran tras su lote en curso y los
+///   resultados que ya habían enviado se entregan igualmente al callback
+///
+/// Con `report` configurado, un hilo aparte informa del progreso a cada intervalo
+/// (a la consola y al `observer`, si lo hay).
 /// 
 /// # Arguments
 /// 
-/// * `config` - Configuración de la búsqueda
+/// * `config` - Configuración de la búsqueda (el matcher decide qué direcciones valen)
 /// * `callback` - Función que se llama cada vez que se encuentra un resultado
 /// 
 /// # Returns
 /// 
-/// Estadísticas de la búsqueda
+/// Estadísticas de la búsqueda (también tras cancelarla), con el motivo de
+/// parada en `stop_reason`
 pub fn search_vanity<F>(config: &Config, mut callback: F) -> Result<SearchStats>
 where
     F: FnMut(VanityResult) -> Result<()>,
@@ -30,71 +47,193 @@ where
     // Validar prefijos
     config.validate_prefixes().map_err(VanityError::InvalidPrefix)?;
 
-    // Configurar el pool global de rayon
-    rayon::ThreadPoolBuilder::new()
+    // Las claves de una fuente determinista se pueden regenerar con la semilla
+    if config.key_source.is_deterministic()
+        && !config.dry_run
+        && !config.allow_deterministic_keys
+    {
+        return Err(VanityError::InvalidConfig(format!(
+            "La fuente de claves {} es determinista: no se guardarán claves inseguras sin autorización explícita",
+            config.key_source.name()
+        )));
+    }
+
+    // Pool propio de la búsqueda: cada worker ocupa un hilo hasta que la
+    // búsqueda termina, así que el pool global (fijado por la primera búsqueda
+    // del proceso) no vale para búsquedas con otro número de hilos
+    let pool = rayon::ThreadPoolBuilder::new()
         .num_threads(config.threads)
-        .build_global()
-        .ok(); // Ignorar error si ya está configurado
+        .thread_name(|index| format!("torge-worker-{}", index))
+        .build()
+        .map_err(|e| {
+            VanityError::InvalidConfig(format!("No se pudo crear el pool de hilos: {}", e))
+        })?;
+
+    // Estado de una búsqueda anterior, si se está reanudando
+    let mut checkpoint = match &config.resume {
+        Some(checkpoint) => {
+            checkpoint.check_config(config)?;
+            checkpoint.clone()
+        }
+        None => Checkpoint::new(config),
+    };
+    let mut progress = Progress::new(config, &checkpoint);
+    let start_time = Instant::now();
+
+    // Nada que hacer si la búsqueda reanudada ya había terminado
+    if progress.goal_reached(config) {
+        remove_checkpoint(config);
+        return Ok(progress.snapshot(&AtomicU64::new(checkpoint.total_attempts), start_time));
+    }
 
-    // Contadores atómicos compartidos entre hilos
-    let attempts = Arc::new(AtomicU64::new(0));
-    let found = Arc::new(AtomicU64::new(0));
+    // Contadores atómicos compartidos entre hilos (acumulados al reanudar)
+    let attempts = Arc::new(AtomicU64::new(checkpoint.total_attempts));
+    let found = Arc::new(AtomicU64::new(progress.results_found as u64));
     let should_stop = Arc::new(AtomicBool::new(false));
 
+    // Informe periódico del progreso en su propio hilo. Informa de los
+    // resultados aceptados, no de `found`: con cupos los workers cuentan
+    // también aciertos que luego se descartan
+    let accepted = Arc::new(AtomicU64::new(progress.results_found as u64));
+    let reporter = config.report.map(|options| {
+        Reporter::spawn(
+            config,
+            options,
+            Arc::clone(&attempts),
+            Arc::clone(&accepted),
+            progress.previous_seconds,
+        )
+    });
+
     // Canal para comunicar resultados desde los hilos workers al hilo principal
     let (tx, rx) = bounded::<VanityResult>(100);
 
-    let start_time = Instant::now();
-
     // Clonar referencias para el closure
     let attempts_clone = Arc::clone(&attempts);
     let found_clone = Arc::clone(&found);
     let should_stop_clone = Arc::clone(&should_stop);
-    let prefixes = config.prefixes.clone();
-    let max_attempts = config.max_attempts;
-    let max_results = config.max_results;
+    let budget = config
+        .max_attempts
+        .map(|max| Arc::new(AtomicU64::new(max.saturating_sub(checkpoint.total_attempts))));
+    let deadline = config.deadline_from(start_time);
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let timed_out_clone = Arc::clone(&timed_out);
+    let threads = config.threads;
+    let cancel = config.cancel.clone();
+
+    // Posición de cada worker (recorrido, paso): con una fuente determinista
+    // se guarda en el checkpoint para continuar exactamente donde se quedó
+    let key_source = Arc::clone(&config.key_source);
+    let positions: Arc<Vec<Mutex<(u64, u64)>>> = Arc::new(
+        (0..threads)
+            .map(|worker| {
+                let start = match key_source.is_deterministic() {
+                    true => (
+                        checkpoint.segments.get(worker).copied().unwrap_or(0),
+                        checkpoint.workers.get(worker).copied().unwrap_or(0),
+                    ),
+                    false => (0, 0),
+                };
+                Mutex::new(start)
+            })
+            .collect(),
+    );
+    let positions_clone = Arc::clone(&positions);
+
+    // Con cupos es el hilo principal quien decide cuándo terminar
+    let max_results = if config.quotas.is_empty() {
+        config.max_results
+    } else {
+        usize::MAX
+    };
+
+    // Matcher compartido: se sustituye al cubrir cupos para dejar de comprobar
+    // esos patrones; los workers lo recargan cuando cambia la generación
+    let initial_matcher = progress
+        .pruned_matcher(config)
+        .unwrap_or_else(|| Arc::clone(&config.matcher));
+    let shared_matcher = Arc::new(RwLock::new(initial_matcher));
+    let matcher_generation = Arc::new(AtomicU64::new(0));
+    let shared_matcher_clone = Arc::clone(&shared_matcher);
+    let matcher_generation_clone = Arc::clone(&matcher_generation);
 
-    // Spawn del worker paralelo
+    // Spawn de los workers paralelos: uno por hilo, cada uno con su recorrido
     let worker_handle = std::thread::spawn(move || {
-        // Usar repeat_n que es el reemplazo de repeatn
-        use rayon::iter::repeat_n;
-        repeat_n((), usize::MAX)
-            .try_for_each_with(tx, |tx, _| {
+        pool.install(|| (0..threads).into_par_iter().for_each_with(tx, |tx, worker| {
+            let position = &positions_clone[worker];
+            let (mut segment, step) = *position.lock().unwrap();
+            let mut walker = key_source.walker(worker, segment, step);
+            let mut generation = matcher_generation_clone.load(Ordering::Acquire);
+            let mut matcher = Arc::clone(&*shared_matcher_clone.read().unwrap());
+
+            loop {
                 // Verificar si debemos detenernos
-                if should_stop_clone.load(Ordering::Relaxed) {
-                    return Err(());
+                if should_stop_clone.load(Ordering::Relaxed) || cancel.is_cancelled() {
+                    break;
                 }
 
-                // Verificar límite de intentos
-                let current_attempts = attempts_clone.fetch_add(1, Ordering::Relaxed) + 1;
-                if let Some(max) = max_attempts {
-                    if current_attempts >= max {
-                        should_stop_clone.store(true, Ordering::Relaxed);
-                        return Err(());
+                // Recargar el matcher si algún cupo se ha cubierto
+                let current_generation = matcher_generation_clone.load(Ordering::Acquire);
+                if current_generation != generation {
+                    generation = current_generation;
+                    matcher = Arc::clone(&*shared_matcher_clone.read().unwrap());
+                }
+
+                // Reservar los intentos del lote: con max_attempts ningún
+                // worker prueba más candidatos de los que quedan
+                let limit = match &budget {
+                    Some(budget) => {
+                        let claim = budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                            (left > 0).then(|| left.saturating_sub(BATCH_SIZE as u64))
+                        });
+                        match claim {
+                            Ok(left) => left.min(BATCH_SIZE as u64) as usize,
+                            Err(_) => {
+                                should_stop_clone.store(true, Ordering::Relaxed);
+                                break;
+                            }
+                        }
                     }
+                    None => BATCH_SIZE,
+                };
+
+                // Verificar límite de tiempo
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    timed_out_clone.store(true, Ordering::Relaxed);
+                    should_stop_clone.store(true, Ordering::Relaxed);
+                    break;
                 }
 
-                // Generar par de claves
-                let (private_key, public_key) = generate_keypair();
+                // Generar el siguiente lote de candidatos
+                let batch = walker.next_batch();
+                let mut tested = limit;
+                let mut finished = false;
+                let mut hit = false;
 
-                // Generar dirección .onion
-                let address = generate_onion_address(&public_key);
+                for (offset, public_key) in batch.public_keys[..limit].iter().enumerate() {
+                    // Evaluar el candidato con el matcher configurado
+                    let candidate = Candidate::new(public_key);
+                    let Some(matched_prefix) = matcher.matches(&candidate) else {
+                        continue;
+                    };
 
-                // Verificar si coincide con algún prefijo
-                if let Some(matched_prefix) = matches_prefix(&address, &prefixes) {
                     let result = VanityResult {
-                        address,
+                        address: candidate.into_address(),
+                        dictionary_word: matcher.dictionary_word(&matched_prefix),
                         matched_prefix,
-                        private_key,
-                        public_key,
+                        private_key: walker.secret_key_at(batch.first_step + offset as u64),
+                        public_key: *public_key,
                         timestamp: chrono::Utc::now(),
                     };
+                    hit = true;
+                    tested = offset + 1;
 
                     // Enviar resultado al hilo principal
                     if tx.send(result).is_err() {
                         // El receptor se cerró, detener
                         should_stop_clone.store(true, Ordering::Relaxed);
-                        return Err(());
+                        finished = true;
+                        break;
                     }
 
                     // Incrementar contador de encontrados
@@ -103,20 +242,71 @@ where
                     // Verificar si alcanzamos el máximo de resultados
                     if current_found >= max_results as u64 {
                         should_stop_clone.store(true, Ordering::Relaxed);
-                        return Err(());
+                        finished = true;
                     }
+                    break;
                 }
 
-                Ok(())
-            })
+                // Tras un acierto se cambia de recorrido, como mkp224o: el resto
+                // de este difiere del encontrado en múltiplos pequeños de 8 y
+                // quien tenga una clave podría calcular las demás
+                if hit {
+                    segment += 1;
+                    *position.lock().unwrap() = (segment, 0);
+                    if !finished {
+                        walker = key_source.walker(worker, segment, 0);
+                    }
+                } else {
+                    *position.lock().unwrap() = (segment, batch.first_step + tested as u64);
+                }
+
+                // Contar solo los candidatos probados y devolver la reserva
+                // que no se llegó a usar
+                attempts_clone.fetch_add(tested as u64, Ordering::Relaxed);
+                if let Some(budget) = &budget {
+                    budget.fetch_add((limit - tested) as u64, Ordering::Relaxed);
+                }
+                if finished {
+                    break;
+                }
+            }
+        }))
     });
 
-    // Hilo principal: recibir y procesar resultados
-    let mut results_count = 0;
+    // Hilo principal: recibir y procesar resultados, guardando el checkpoint
+    // periódicamente (también sin resultados, gracias al timeout)
     let mut last_error = None;
+    let tick = config
+        .checkpoint_interval
+        .unwrap_or(Duration::from_secs(60));
+    let mut last_checkpoint = Instant::now();
+
+    loop {
+        let result = match rx.recv_timeout(tick) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                let stats = progress.snapshot(&attempts, start_time);
+                write_checkpoint(config, &mut checkpoint, &stats, &positions);
+                last_checkpoint = Instant::now();
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
 
-    for result in rx {
-        results_count += 1;
+        let index = progress.pattern_index(&result.matched_prefix);
+
+        // Con cupos se descartan los aciertos de patrones ya cubiertos (los
+        // workers aún no habían recargado el matcher)
+        if progress.is_filled(index, config) {
+            continue;
+        }
+
+        progress.results_found += 1;
+        progress.patterns[index].found += 1;
+        accepted.store(progress.results_found as u64, Ordering::Relaxed);
+        if let Some(observer) = &config.observer {
+            observer.on_result(&result);
+        }
 
         // Llamar al callback con el resultado
         if let Err(e) = callback(result) {
@@ -125,32 +315,310 @@ where
             break;
         }
 
-        // Verificar si alcanzamos el máximo
-        if results_count >= config.max_results {
+        // Verificar si se alcanzó el objetivo (máximo de resultados o cupos)
+        if progress.goal_reached(config) {
             should_stop.store(true, Ordering::Relaxed);
             break;
         }
+
+        // Dejar de buscar el patrón cuyo cupo acaba de cubrirse
+        if progress.is_filled(index, config) {
+            if let Some(pruned) = progress.pruned_matcher(config) {
+                *shared_matcher.write().unwrap() = pruned;
+                matcher_generation.fetch_add(1, Ordering::Release);
+            }
+        }
+
+        if last_checkpoint.elapsed() >= tick {
+            let stats = progress.snapshot(&attempts, start_time);
+            write_checkpoint(config, &mut checkpoint, &stats, &positions);
+            last_checkpoint = Instant::now();
+        }
     }
 
-    // Esperar a que terminen los workers
+    // Esperar a que terminen los workers (sin receptor, ninguno queda
+    // bloqueado enviando un resultado que ya no se va a procesar)
+    drop(rx);
     let _ = worker_handle.join();
+    if let Some(reporter) = reporter {
+        reporter.finish();
+    }
+
+    // Recopilar estadísticas (acumuladas con las de la sesión anterior)
+    let mut stats = progress.snapshot(&attempts, start_time);
+    stats.stop_reason = if progress.goal_reached(config) {
+        StopReason::GoalReached
+    } else if config.cancel.is_cancelled() {
+        StopReason::Cancelled
+    } else if timed_out.load(Ordering::Relaxed) {
+        StopReason::Deadline
+    } else {
+        StopReason::MaxAttempts
+    };
+
+    // Guardar el estado final; si el objetivo se cumplió ya no hace falta
+    if progress.goal_reached(config) && last_error.is_none() {
+        remove_checkpoint(config);
+    } else {
+        write_checkpoint(config, &mut checkpoint, &stats, &positions);
+    }
 
     // Si hubo un error en el callback, propagarlo
     if let Some(e) = last_error {
         return Err(e);
     }
 
-    // Recopilar estadísticas
-    let elapsed = start_time.elapsed();
-    let stats = SearchStats {
-        total_attempts: attempts.load(Ordering::Relaxed),
-        results_found: results_count,
-        elapsed_seconds: elapsed.as_secs_f64(),
-    };
+    if let Some(observer) = &config.observer {
+        observer.on_finish(&stats);
+    }
 
     Ok(stats)
 }
 
+/// Lanza la búsqueda en segundo plano y entrega los resultados como un iterador
+///
+/// La búsqueda corre en un hilo propio con `search_vanity`, atenta a
+/// `config.cancel` pero con una señal derivada (ver `CancelToken::child`): al
+/// soltar el iterador, o al cortarlo con `take`/`break`, solo se detiene esta
+/// búsqueda y los workers paran tras su lote en curso. Cada resultado espera a
+/// que se pida el siguiente, de modo que los que no se lean se pierden.
+///
+/// # Arguments
+///
+/// * `config` - Configuración de la búsqueda
+///
+/// # Returns
+///
+/// Iterador que termina cuando la búsqueda se detiene por sí sola
+pub fn search_iter(config: &Config) -> SearchIter {
+    let cancel = config.cancel.child();
+    let config = Config {
+        cancel: cancel.clone(),
+        ..config.clone()
+    };
+    let (tx, rx) = bounded::<VanityResult>(0);
+
+    // Si el iterador ya no está, el resultado se descarta y la búsqueda sigue
+    // hasta notar la cancelación, para conservar sus estadísticas
+    let handle = std::thread::spawn(move || {
+        search_vanity(&config, |result| {
+            let _ = tx.send(result);
+            Ok(())
+        })
+    });
+
+    SearchIter {
+        results: rx,
+        cancel,
+        handle: Some(handle),
+    }
+}
+
+/// Resultados de una búsqueda lanzada con `search_iter`
+///
+/// Al soltarse detiene la búsqueda y espera a que terminen los workers.
+pub struct SearchIter {
+    /// Resultados enviados por el hilo de la búsqueda
+    results: Receiver<VanityResult>,
+    /// Señal propia de esta búsqueda
+    cancel: CancelToken,
+    /// Hilo de la búsqueda (None una vez recogido)
+    handle: Option<JoinHandle<Result<SearchStats>>>,
+}
+
+impl SearchIter {
+    /// Detiene la búsqueda si sigue en marcha y devuelve sus estadísticas
+    ///
+    /// # Returns
+    ///
+    /// Las estadísticas de `search_vanity`: `stop_reason` es `Cancelled` si la
+    /// búsqueda no había terminado todavía
+    pub fn finish(mut self) -> Result<SearchStats> {
+        match self.stop() {
+            Some(Ok(outcome)) => outcome,
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => Err(VanityError::Cancelled),
+        }
+    }
+
+    /// Cancela la búsqueda, deja de recibir resultados y espera al hilo
+    fn stop(&mut self) -> Option<std::thread::Result<Result<SearchStats>>> {
+        let handle = self.handle.take()?;
+        self.cancel.cancel();
+        self.results = never();
+        Some(handle.join())
+    }
+}
+
+impl Iterator for SearchIter {
+    type Item = VanityResult;
+
+    fn next(&mut self) -> Option<VanityResult> {
+        self.results.recv().ok()
+    }
+}
+
+impl Drop for SearchIter {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Progreso acumulado de una búsqueda (incluida la sesión anterior al reanudar)
+struct Progress {
+    /// Resultados aceptados
+    results_found: usize,
+    /// Progreso por patrón
+    patterns: Vec<PatternStats>,
+    /// Tiempo acumulado de sesiones anteriores
+    previous_seconds: f64,
+}
+
+impl Progress {
+    /// Estado inicial: cupos a cero o el progreso guardado en el checkpoint
+    fn new(config: &Config, checkpoint: &Checkpoint) -> Self {
+        let mut progress = Self {
+            results_found: checkpoint.results_found,
+            patterns: config
+                .quotas
+                .iter()
+                .map(|quota| PatternStats {
+                    label: quota.label.clone(),
+                    found: 0,
+                    quota: Some(quota.target),
+                })
+                .collect(),
+            previous_seconds: checkpoint.elapsed_seconds,
+        };
+
+        for saved in &checkpoint.patterns {
+            let index = progress.pattern_index(&saved.label);
+            progress.patterns[index].found = saved.found;
+        }
+        progress
+    }
+
+    /// Posición de un patrón en la lista, añadiéndolo si es nuevo
+    fn pattern_index(&mut self, label: &str) -> usize {
+        match self.patterns.iter().position(|p| p.label == label) {
+            Some(index) => index,
+            None => {
+                self.patterns.push(PatternStats {
+                    label: label.to_string(),
+                    found: 0,
+                    quota: None,
+                });
+                self.patterns.len() - 1
+            }
+        }
+    }
+
+    /// Indica si se alcanzó `max_results` o, con cupos, si están todos cubiertos
+    fn goal_reached(&self, config: &Config) -> bool {
+        if config.quotas.is_empty() {
+            self.results_found >= config.max_results
+        } else {
+            self.patterns
+                .iter()
+                .all(|p| p.quota.is_none() || p.is_filled())
+        }
+    }
+
+    /// Resultados de los patrones sin cupo propio
+    fn unquoted_found(&self) -> usize {
+        self.patterns
+            .iter()
+            .filter(|p| p.quota.is_none())
+            .map(|p| p.found)
+            .sum()
+    }
+
+    /// Indica si el patrón `index` ya no admite resultados: su cupo está
+    /// cubierto o, sin cupo propio, los patrones sin cupo llegaron a `max_results`
+    fn is_filled(&self, index: usize, config: &Config) -> bool {
+        if config.quotas.is_empty() {
+            return false;
+        }
+        match self.patterns[index].quota {
+            Some(_) => self.patterns[index].is_filled(),
+            None => self.unquoted_found() >= config.max_results,
+        }
+    }
+
+    /// Matcher sin los patrones cubiertos (None si no cambia)
+    fn pruned_matcher(&self, config: &Config) -> Option<Arc<dyn Matcher>> {
+        let mut filled: HashSet<String> = self
+            .patterns
+            .iter()
+            .filter(|p| p.is_filled())
+            .map(|p| p.label.clone())
+            .collect();
+        if !config.quotas.is_empty() && self.unquoted_found() >= config.max_results {
+            filled.extend(
+                config
+                    .prefixes
+                    .iter()
+                    .filter(|prefix| !config.quotas.iter().any(|quota| quota.label == **prefix))
+                    .cloned(),
+            );
+        }
+        if filled.is_empty() {
+            return None;
+        }
+        config.matcher.without_labels(&filled)
+    }
+
+    /// Estadísticas acumuladas hasta este momento
+    fn snapshot(&self, attempts: &AtomicU64, start_time: Instant) -> SearchStats {
+        SearchStats {
+            total_attempts: attempts.load(Ordering::Relaxed),
+            results_found: self.results_found,
+            elapsed_seconds: self.previous_seconds + start_time.elapsed().as_secs_f64(),
+            patterns: self.patterns.clone(),
+            stop_reason: StopReason::GoalReached,
+        }
+    }
+}
+
+/// Actualiza y guarda el checkpoint si la configuración lo pide
+///
+/// Un fallo al escribirlo no detiene la búsqueda: solo se advierte.
+fn write_checkpoint(
+    config: &Config,
+    checkpoint: &mut Checkpoint,
+    stats: &SearchStats,
+    positions: &[Mutex<(u64, u64)>],
+) {
+    if config.checkpoint_interval.is_none() {
+        return;
+    }
+
+    if config.key_source.is_deterministic() {
+        (checkpoint.segments, checkpoint.workers) = positions
+            .iter()
+            .map(|position| *position.lock().unwrap())
+            .unzip();
+    }
+
+    checkpoint.total_attempts = stats.total_attempts;
+    checkpoint.elapsed_seconds = stats.elapsed_seconds;
+    checkpoint.results_found = stats.results_found;
+    checkpoint.patterns = stats.patterns.clone();
+    if let Err(e) = checkpoint.save(&config.output_dir) {
+        eprintln!("⚠️  No se pudo guardar el checkpoint: {}", e);
+    }
+}
+
+/// Elimina el checkpoint de una búsqueda terminada
+fn remove_checkpoint(config: &Config) {
+    if config.checkpoint_interval.is_none() {
+        return;
+    }
+    if let Err(e) = Checkpoint::remove(&config.output_dir) {
+        eprintln!("⚠️  No se pudo eliminar el checkpoint: {}", e);
+    }
+}
+
 /// Muestra estadísticas de progreso durante la búsqueda
 /// 
 /// # Arguments
@@ -183,13 +651,89 @@ pub fn estimate_time(prefix: &str, rate: f64) -> f64 {
     // El alfabeto base32 tiene 32 caracteres
     // La probabilidad de encontrar un prefijo de longitud n es 1/32^n
     let probability = 1.0 / 32f64.powi(prefix.len() as i32);
+    estimate_time_for_probability(probability, rate)
+}
+
+/// Estima el tiempo necesario para un patrón con probabilidad conocida
+///
+/// # Arguments
+///
+/// * `probability` - Probabilidad de que un intento coincida
+/// * `rate` - Tasa de generación (intentos por segundo)
+///
+/// # Returns
+///
+/// Tiempo estimado en segundos (infinito si el patrón es imposible)
+pub fn estimate_time_for_probability(probability: f64, rate: f64) -> f64 {
+    if probability <= 0.0 || rate <= 0.0 {
+        return f64::INFINITY;
+    }
+
     let expected_attempts = 1.0 / probability;
-    
-    if rate > 0.0 {
-        expected_attempts / rate
-    } else {
-        f64::INFINITY
+    expected_attempts / rate
+}
+
+/// Probabilidades de éxito para las que se muestra el tiempo necesario
+pub const ETA_CONFIDENCES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Intentos necesarios para encontrar el patrón con una probabilidad dada
+///
+/// El número de intentos hasta el primer acierto sigue una distribución
+/// geométrica: con n intentos la probabilidad de éxito es 1 - (1 - p)^n, así
+/// que n = ln(1 - confianza) / ln(1 - p). La media (1/p) corresponde a ~63%.
+///
+/// # Arguments
+///
+/// * `probability` - Probabilidad de que un intento coincida
+/// * `confidence` - Probabilidad de éxito deseada (0..1)
+///
+/// # Returns
+///
+/// Número de intentos (infinito si el patrón es imposible)
+pub fn attempts_for_confidence(probability: f64, confidence: f64) -> f64 {
+    if probability <= 0.0 {
+        return f64::INFINITY;
     }
+    if probability >= 1.0 {
+        return 1.0;
+    }
+    (-confidence).ln_1p() / (-probability).ln_1p()
+}
+
+/// Tiempo necesario para encontrar el patrón con una probabilidad dada
+///
+/// Como la distribución no tiene memoria, también es el tiempo restante en
+/// una búsqueda en curso que aún no lo ha encontrado.
+///
+/// # Arguments
+///
+/// * `probability` - Probabilidad de que un intento coincida
+/// * `confidence` - Probabilidad de éxito deseada (0..1)
+/// * `rate` - Tasa de generación (intentos por segundo)
+///
+/// # Returns
+///
+/// Tiempo en segundos (infinito si el patrón es imposible)
+pub fn time_for_confidence(probability: f64, confidence: f64, rate: f64) -> f64 {
+    if rate <= 0.0 {
+        return f64::INFINITY;
+    }
+    attempts_for_confidence(probability, confidence) / rate
+}
+
+/// Tiempos hasta el 50%, 90% y 99% de probabilidad de éxito, en una línea
+pub fn format_eta_percentiles(probability: f64, rate: f64) -> String {
+    ETA_CONFIDENCES
+        .iter()
+        .map(|&confidence| {
+            format!(
+                "{:.0}%: ~{}",
+                confidence * 100.0,
+                format_duration(time_for_confidence(probability, confidence, rate))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
 }
 
 /// Formatea un tiempo en segundos a una cadena legible
@@ -220,9 +764,75 @@ pub fn format_duration(seconds: f64) -> String {
     }
 }
 
+/// Interpreta una duración como "6h", "90m", "1h30m", "2d" o "45" (segundos)
+///
+/// # Arguments
+///
+/// * `text` - Duración con unidades d, h, m o s (sin unidad, segundos)
+///
+/// # Returns
+///
+/// La duración, o un error si el formato no es válido o es cero
+pub fn parse_duration(text: &str) -> std::result::Result<Duration, String> {
+    let invalid = || format!("Duración inválida '{}': use por ejemplo 6h, 90m o 1h30m", text);
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+    for ch in text.chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+        let unit = match ch.to_ascii_lowercase() {
+            'd' => 86_400,
+            'h' => 3_600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        seconds = value
+            .checked_mul(unit)
+            .and_then(|value| seconds.checked_add(value))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        seconds = seconds.checked_add(value).ok_or_else(invalid)?;
+    }
+
+    if seconds == 0 {
+        return Err("La duración debe ser mayor que 0".to_string());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Convierte una fecha y hora en el `Instant` del reloj monotónico equivalente
+///
+/// # Arguments
+///
+/// * `until` - Momento en que debe detenerse la búsqueda
+///
+/// # Returns
+///
+/// El `Instant` correspondiente, o un error si ese momento ya pasó
+pub fn deadline_at(until: chrono::DateTime<chrono::Utc>) -> std::result::Result<Instant, String> {
+    (until - chrono::Utc::now())
+        .to_std()
+        .map(|remaining| Instant::now() + remaining)
+        .map_err(|_| format!("La hora límite {} ya ha pasado", until.to_rfc3339()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::key_source::{OsKeySource, SeededKeySource};
+    use crate::matcher::{Matcher, PrefixMatcher};
 
     #[test]
     fn test_estimate_time() {
@@ -235,6 +845,34 @@ mod tests {
         let time = estimate_time("ab", 1000.0);
         // Probabilidad = 1/1024, esperamos ~1024 intentos, a 1000/s = ~1s
         assert!(time > 0.5 && time < 2.0);
+
+        // Un sufijo "yd" tiene probabilidad 1/4
+        let time = estimate_time_for_probability(0.25, 1000.0);
+        assert!((time - 0.004).abs() < 1e-9);
+        assert!(estimate_time_for_probability(0.0, 1000.0).is_infinite());
+    }
+
+    #[test]
+    fn test_eta_percentiles() {
+        // Con p = 1/2: 1 intento da 50%, 4 intentos ~94%
+        assert!((attempts_for_confidence(0.5, 0.5) - 1.0).abs() < 1e-9);
+        assert!((attempts_for_confidence(0.5, 0.9375) - 4.0).abs() < 1e-9);
+
+        // Para un prefijo de 8 caracteres: la mediana es ln(2) veces la media
+        let p = 1.0 / 32f64.powi(8);
+        let median = attempts_for_confidence(p, 0.5);
+        assert!((median * p - std::f64::consts::LN_2).abs() < 1e-6);
+        assert!(attempts_for_confidence(p, 0.99) > attempts_for_confidence(p, 0.9));
+
+        assert!(attempts_for_confidence(0.0, 0.5).is_infinite());
+        assert_eq!(attempts_for_confidence(1.0, 0.99), 1.0);
+        assert!(time_for_confidence(p, 0.5, 0.0).is_infinite());
+
+        // 1/1024 a 1024/s: 50% en ~0.7s, 90% en ~2.4s, 99% en ~4.7s
+        assert_eq!(
+            format_eta_percentiles(1.0 / 1024.0, 1024.0),
+            "50%: ~0s | 90%: ~2s | 99%: ~4s"
+        );
     }
 
     #[test]
@@ -246,16 +884,30 @@ mod tests {
     }
 
     #[test]
-    fn test_search_vanity_basic() {
-        use std::path::PathBuf;
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("6h"), Ok(Duration::from_secs(6 * 3600)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("2d"), Ok(Duration::from_secs(2 * 86_400)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration(" 10M "), Ok(Duration::from_secs(600)));
 
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("6x").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("99999999999999999999d").is_err());
+
+        let future = chrono::Utc::now() + chrono::Duration::hours(1);
+        let deadline = deadline_at(future).unwrap();
+        assert!(deadline > Instant::now() + Duration::from_secs(3500));
+        assert!(deadline_at(chrono::Utc::now() - chrono::Duration::hours(1)).is_err());
+    }
+
+    #[test]
+    fn test_search_vanity_basic() {
         let config = Config {
-            prefixes: vec!["a".to_string()], // Prefijo muy común
-            threads: 2,
-            max_results: 1,
             max_attempts: Some(100000), // Límite de seguridad
-            output_dir: PathBuf::from("./test_output"),
-            dry_run: true,
+            ..Config::for_test(&["a"]) // Prefijo muy común
         };
 
         let mut found_count = 0;
@@ -269,4 +921,413 @@ mod tests {
         let stats = result.unwrap();
         assert!(stats.results_found > 0 || stats.total_attempts >= 100000);
     }
+
+    /// Matcher de prueba: direcciones cuyo segundo carácter es un dígito
+    #[derive(Debug)]
+    struct SecondCharDigit;
+
+    impl Matcher for SecondCharDigit {
+        fn name(&self) -> &str {
+            "segundo carácter numérico"
+        }
+
+        fn matches(&self, candidate: &Candidate) -> Option<String> {
+            let second = candidate.address().chars().nth(1)?;
+            second.is_ascii_digit().then(|| second.to_string())
+        }
+
+        fn probability(&self) -> f64 {
+            6.0 / 32.0
+        }
+    }
+
+    #[test]
+    fn test_search_vanity_cancel() {
+        let config = Config {
+            max_results: usize::MAX,
+            max_attempts: Some(10_000_000), // Límite de seguridad
+            ..Config::for_test(&["a"])
+        };
+
+        // Cancelar en el primer resultado: los ya enviados se entregan todos
+        let mut delivered = 0;
+        let stats = search_vanity(&config, |_| {
+            delivered += 1;
+            config.cancel.cancel();
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(stats.stop_reason, StopReason::Cancelled);
+        assert_eq!(stats.results_found, delivered);
+        assert!(delivered >= 1);
+        assert!(stats.total_attempts < 10_000_000);
+
+        // Cancelada de antemano: los workers no llegan a empezar
+        let stats = search_vanity(&config, |_| Ok(())).unwrap();
+        assert_eq!(stats.stop_reason, StopReason::Cancelled);
+        assert_eq!(stats.total_attempts, 0);
+
+        // Sin cancelar, agotar los intentos es otro motivo de parada; se
+        // prueban exactamente los intentos permitidos, aunque no llenen un lote
+        let config = Config {
+            max_attempts: Some(BATCH_SIZE as u64 + 7),
+            cancel: CancelToken::new(),
+            matcher: Arc::new(PrefixMatcher::new(&["aaaaaaaa".to_string()]).unwrap()),
+            ..config
+        };
+        let stats = search_vanity(&config, |_| Ok(())).unwrap();
+        assert_eq!(stats.stop_reason, StopReason::MaxAttempts);
+        assert_eq!(stats.total_attempts, BATCH_SIZE as u64 + 7);
+    }
+
+    #[test]
+    fn test_search_iter() {
+        let config = Config {
+            max_results: usize::MAX,
+            ..Config::for_test(&["a"])
+        };
+
+        // take(n) corta una búsqueda sin límites: al soltar el iterador paran los workers
+        let results: Vec<VanityResult> = search_iter(&config).take(3).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.address.starts_with('a')));
+
+        // Salir del bucle y pedir las estadísticas: solo se cancela esta búsqueda
+        let mut iter = search_iter(&config);
+        let mut seen = 0;
+        for result in iter.by_ref() {
+            assert!(result.address.starts_with('a'));
+            seen += 1;
+            if seen == 2 {
+                break;
+            }
+        }
+        let stats = iter.finish().unwrap();
+        assert_eq!(stats.stop_reason, StopReason::Cancelled);
+        assert!(stats.results_found >= 1);
+        assert!(!config.cancel.is_cancelled());
+
+        // Con un objetivo, el iterador termina solo
+        let limited = Config {
+            max_results: 2,
+            ..config.clone()
+        };
+        let mut iter = search_iter(&limited);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.finish().unwrap().stop_reason, StopReason::GoalReached);
+
+        // Cancelar la señal de la configuración también detiene el iterador
+        config.cancel.cancel();
+        let mut iter = search_iter(&config);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.finish().unwrap().stop_reason, StopReason::Cancelled);
+    }
+
+    /// Fuente que anota el hilo en el que arranca cada worker
+    #[derive(Debug, Default)]
+    struct ThreadRecorder(std::sync::Mutex<HashSet<String>>);
+
+    impl crate::key_source::KeySource for ThreadRecorder {
+        fn name(&self) -> String {
+            "ThreadRecorder".to_string()
+        }
+
+        fn walker(&self, worker: usize, segment: u64, start_step: u64) -> crate::generator::KeyWalker {
+            let name = std::thread::current().name().unwrap_or_default().to_string();
+            self.0.lock().unwrap().insert(name);
+            OsKeySource.walker(worker, segment, start_step)
+        }
+    }
+
+    #[test]
+    fn test_search_vanity_thread_pools() {
+        // Patrón inalcanzable durante 300 ms: todos los workers llegan a arrancar
+        let config = |threads: usize, recorder: &Arc<ThreadRecorder>| Config {
+            threads,
+            max_time: Some(Duration::from_millis(300)),
+            key_source: recorder.clone(),
+            ..Config::for_test(&["aaaaaaaaaa"])
+        };
+        let worker_threads = |recorder: &ThreadRecorder| {
+            let names = recorder.0.lock().unwrap();
+            assert!(names.iter().all(|name| name.starts_with("torge-worker-")), "{:?}", names);
+            names.len()
+        };
+
+        // Una búsqueda tras otra, con menos y luego más hilos
+        let small = Arc::new(ThreadRecorder::default());
+        search_vanity(&config(1, &small), |_| Ok(())).unwrap();
+        let large = Arc::new(ThreadRecorder::default());
+        search_vanity(&config(3, &large), |_| Ok(())).unwrap();
+        assert_eq!(worker_threads(&small), 1);
+        assert_eq!(worker_threads(&large), 3);
+
+        // Dos búsquedas a la vez, cada una con su presupuesto de hilos
+        let first = Arc::new(ThreadRecorder::default());
+        let second = Arc::new(ThreadRecorder::default());
+        let first_config = config(2, &first);
+        let second_config = config(4, &second);
+        std::thread::scope(|scope| {
+            scope.spawn(|| search_vanity(&first_config, |_| Ok(())).unwrap());
+            scope.spawn(|| search_vanity(&second_config, |_| Ok(())).unwrap());
+        });
+        assert_eq!(worker_threads(&first), 2);
+        assert_eq!(worker_threads(&second), 4);
+    }
+
+    #[test]
+    fn test_search_vanity_time_limits() {
+        let config = Config {
+            max_time: Some(Duration::from_millis(200)),
+            ..Config::for_test(&["aaaaaaaa"]) // Prácticamente imposible
+        };
+
+        let start = Instant::now();
+        let stats = search_vanity(&config, |_| Ok(())).unwrap();
+        assert_eq!(stats.stop_reason, StopReason::Deadline);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(stats.total_attempts > 0);
+
+        // Gana el límite que llegue antes
+        let now = Instant::now();
+        let config = Config {
+            deadline: Some(now + Duration::from_secs(1)),
+            max_time: Some(Duration::from_secs(60)),
+            ..config
+        };
+        assert_eq!(config.deadline_from(now), Some(now + Duration::from_secs(1)));
+        let config = Config {
+            deadline: None,
+            ..config
+        };
+        assert_eq!(config.deadline_from(now), Some(now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_search_vanity_quotas() {
+        use crate::types::Quota;
+
+        let config = Config {
+            quotas: vec![
+                Quota {
+                    label: "a".to_string(),
+                    target: 3,
+                },
+                Quota {
+                    label: "b".to_string(),
+                    target: 1,
+                },
+            ],
+            max_attempts: Some(1_000_000), // Límite de seguridad
+            ..Config::for_test(&["a", "b", "c"])
+        };
+
+        let mut labels = Vec::new();
+        let stats = search_vanity(&config, |result| {
+            labels.push(result.matched_prefix);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(labels.iter().filter(|label| *label == "a").count(), 3);
+        assert_eq!(labels.iter().filter(|label| *label == "b").count(), 1);
+
+        // "c" no tiene cupo: comparte max_results y no retiene la búsqueda
+        let unquoted = labels.iter().filter(|label| *label == "c").count();
+        assert!(unquoted <= 1);
+        assert_eq!(stats.results_found, 4 + unquoted);
+        assert!(stats
+            .patterns
+            .iter()
+            .all(|pattern| pattern.is_filled() || pattern.quota.is_none()));
+    }
+
+    #[test]
+    fn test_search_vanity_reports_accepted_results() {
+        use crate::reporter::{ProgressReport, ReportOptions, ReportStyle, SearchObserver};
+        use crate::types::Quota;
+
+        /// Guarda el mayor número de resultados informado
+        #[derive(Debug, Default)]
+        struct MaxFound(AtomicU64);
+
+        impl SearchObserver for MaxFound {
+            fn on_progress(&self, report: &ProgressReport) {
+                self.0
+                    .fetch_max(report.stats.results_found as u64, Ordering::Relaxed);
+            }
+        }
+
+        // Tras cubrir el cupo de "a" los workers aún encuentran "a" hasta
+        // recargar el matcher; esos aciertos descartados no se informan
+        let observer = Arc::new(MaxFound::default());
+        let config = Config {
+            quotas: vec![
+                Quota {
+                    label: "a".to_string(),
+                    target: 1,
+                },
+                Quota {
+                    label: "77777".to_string(),
+                    target: 1,
+                },
+            ],
+            max_attempts: Some(200_000),
+            report: Some(ReportOptions {
+                interval: Duration::from_millis(1),
+                style: ReportStyle::Silent,
+            }),
+            observer: Some(Arc::clone(&observer) as Arc<dyn SearchObserver>),
+            ..Config::for_test(&["a", "77777"])
+        };
+
+        let stats = search_vanity(&config, |_| Ok(())).unwrap();
+        assert!(observer.0.load(Ordering::Relaxed) <= stats.results_found as u64);
+    }
+
+    #[test]
+    fn test_search_vanity_checkpoint_resume() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = Config {
+            max_attempts: Some(5_000),
+            checkpoint_interval: Some(Duration::from_secs(60)),
+            output_dir: temp_dir.path().to_path_buf(),
+            dry_run: false,
+            ..Config::for_test(&["zzzzzzzz"])
+        };
+
+        // La búsqueda se corta por intentos: el checkpoint queda guardado
+        let first = search_vanity(&config, |_| Ok(())).unwrap();
+        let checkpoint = Checkpoint::load(temp_dir.path()).unwrap();
+        assert_eq!(checkpoint.total_attempts, first.total_attempts);
+
+        // Al reanudar, los intentos y el tiempo se acumulan
+        config.max_attempts = Some(10_000);
+        config.resume = Some(checkpoint);
+        let second = search_vanity(&config, |_| Ok(())).unwrap();
+        assert!(second.total_attempts >= 10_000);
+        assert!(second.elapsed_seconds >= first.elapsed_seconds);
+
+        // Un checkpoint de otra búsqueda se rechaza
+        let other = vec!["abc".to_string()];
+        config.matcher = Arc::new(PrefixMatcher::new(&other).unwrap());
+        config.prefixes = other;
+        config.resume = Some(Checkpoint::load(temp_dir.path()).unwrap());
+        assert!(search_vanity(&config, |_| Ok(())).is_err());
+
+        // Una búsqueda que cumple su objetivo elimina el checkpoint
+        config.resume = None;
+        config.prefixes = vec!["a".to_string()];
+        config.matcher = Arc::new(PrefixMatcher::new(&config.prefixes).unwrap());
+        config.max_attempts = Some(1_000_000);
+        search_vanity(&config, |_| Ok(())).unwrap();
+        assert!(!Checkpoint::path(temp_dir.path()).exists());
+    }
+
+    #[test]
+    fn test_search_vanity_seeded() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = Config {
+            threads: 1,
+            max_results: 4,
+            max_attempts: Some(1_000_000), // Límite de seguridad
+            key_source: Arc::new(SeededKeySource::new(7)),
+            output_dir: temp_dir.path().to_path_buf(),
+            ..Config::for_test(&["ab"])
+        };
+
+        let run = |config: &Config| {
+            let mut addresses = Vec::new();
+            search_vanity(config, |result| {
+                addresses.push(result.address);
+                Ok(())
+            })
+            .unwrap();
+            addresses
+        };
+
+        // La misma semilla reproduce exactamente la búsqueda
+        let full = run(&config);
+        assert_eq!(full.len(), 4);
+        assert_eq!(run(&config), full);
+
+        // Cortada por intentos y reanudada, continúa donde se quedó
+        config.checkpoint_interval = Some(Duration::from_secs(60));
+        config.max_attempts = Some(8 * BATCH_SIZE as u64);
+        let mut resumed = run(&config);
+        config.max_attempts = Some(1_000_000);
+        config.resume = Some(Checkpoint::load(temp_dir.path()).unwrap());
+        resumed.extend(run(&config));
+        assert_eq!(resumed, full);
+
+        // Sin autorización explícita no se aceptan claves que se guardarían
+        config.resume = None;
+        config.dry_run = false;
+        let refused = search_vanity(&config, |_| Ok(()));
+        assert!(matches!(refused, Err(VanityError::InvalidConfig(_))));
+
+        config.allow_deterministic_keys = true;
+        assert!(search_vanity(&config, |_| Ok(())).is_ok());
+    }
+
+    #[test]
+    fn test_search_vanity_reseeds_after_hit() {
+        use curve25519_dalek::Scalar;
+
+        // Un solo worker encuentra todos los resultados
+        let config = Config {
+            threads: 1,
+            max_results: 6,
+            max_attempts: Some(1_000_000), // Límite de seguridad
+            ..Config::for_test(&["a"])
+        };
+        let mut keys = Vec::new();
+        search_vanity(&config, |result| {
+            keys.push(result.private_key);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(keys.len(), 6);
+
+        // En un mismo recorrido s2 = s1 + 8k con k pequeño y el mismo nonce
+        let eighth = Scalar::from(8u64).invert();
+        let scalar = |key: &[u8; 64]| Scalar::from_bytes_mod_order(key[..32].try_into().unwrap());
+        for (i, first) in keys.iter().enumerate() {
+            for second in &keys[i + 1..] {
+                assert_ne!(first[32..], second[32..]);
+                for steps in [
+                    (scalar(second) - scalar(first)) * eighth,
+                    (scalar(first) - scalar(second)) * eighth,
+                ] {
+                    assert!(steps.to_bytes()[8..].iter().any(|&byte| byte != 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_vanity_custom_matcher() {
+        let config = Config {
+            prefixes: Vec::new(),
+            matcher: Arc::new(SecondCharDigit),
+            max_results: 3,
+            max_attempts: Some(100000),
+            ..Config::for_test(&["a"])
+        };
+
+        let stats = search_vanity(&config, |result| {
+            let second = result.address.chars().nth(1).unwrap();
+            assert!(second.is_ascii_digit());
+            assert_eq!(result.matched_prefix, second.to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(stats.results_found, 3);
+    }
 }
@@ -0,0 +1,469 @@
+use crate::error::{Result, VanityError};
+use crate::generator::explain_impossible_suffix;
+use crate::key_source::{KeySource, OsKeySource};
+use crate::matcher::{AnyMatcher, Matcher, PrefixMatcher, RegexMatcher, SuffixMatcher};
+use crate::reporter::{ReportOptions, ReportStyle, SearchObserver};
+use crate::search::search_vanity;
+use crate::storage;
+use crate::types::{CancelToken, Config, SearchStats, VanityResult};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Intervalo de progreso por defecto cuando hay observador
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Destino de los resultados de un `Searcher`
+///
+/// Se llama una vez por resultado aceptado; un error detiene la búsqueda y
+/// `Searcher::run` lo devuelve. Cualquier closure
+/// `FnMut(&VanityResult) -> Result<()>` sirve como destino.
+pub trait ResultSink: Send {
+    /// Recibe un resultado
+    fn accept(&mut self, result: &VanityResult) -> Result<()>;
+}
+
+impl<F> ResultSink for F
+where
+    F: FnMut(&VanityResult) -> Result<()> + Send,
+{
+    fn accept(&mut self, result: &VanityResult) -> Result<()> {
+        self(result)
+    }
+}
+
+/// Destino que guarda cada resultado con `storage::save_result`
+#[derive(Debug, Clone)]
+pub struct DirectorySink {
+    /// Directorio de salida
+    output_dir: PathBuf,
+    /// Hilos de la búsqueda (se anotan en los metadatos)
+    threads: usize,
+}
+
+impl DirectorySink {
+    /// Crea el directorio de salida (con su README) si no existe
+    ///
+    /// # Arguments
+    ///
+    /// * `output_dir` - Directorio donde guardar las claves
+    /// * `threads` - Hilos de la búsqueda, para los metadatos
+    pub fn new(output_dir: impl Into<PathBuf>, threads: usize) -> Result<Self> {
+        let output_dir = output_dir.into();
+        std::fs::create_dir_all(&output_dir).map_err(|e| {
+            VanityError::DirectoryCreation(format!("No se pudo crear {}: {}", output_dir.display(), e))
+        })?;
+        storage::create_readme(&output_dir)?;
+        Ok(Self {
+            output_dir,
+            threads,
+        })
+    }
+}
+
+impl ResultSink for DirectorySink {
+    fn accept(&mut self, result: &VanityResult) -> Result<()> {
+        storage::save_result(result, &self.output_dir, self.threads).map(|_| ())
+    }
+}
+
+/// Constructor de búsquedas para usar la generación desde otro programa
+///
+/// Reúne en una sola API lo que la CLI configura con flags: patrones, hilos,
+/// límites, fuente de claves, destinos de los resultados y observador.
+///
+/// ```no_run
+/// use vanity_onion_v3::searcher::Searcher;
+/// use std::time::Duration;
+///
+/// let stats = Searcher::new()
+///     .prefix("shop")
+///     .max_results(2)
+///     .max_time(Duration::from_secs(600))
+///     .save_to("./output")
+///     .run()?;
+/// println!("{} resultados en {} intentos", stats.results_found, stats.total_attempts);
+/// # Ok::<(), vanity_onion_v3::error::VanityError>(())
+/// ```
+pub struct Searcher {
+    /// Prefijos a buscar
+    prefixes: Vec<String>,
+    /// Sufijos a buscar
+    suffixes: Vec<String>,
+    /// Expresiones regulares sobre la dirección
+    regexes: Vec<String>,
+    /// Matchers propios
+    matchers: Vec<Arc<dyn Matcher>>,
+    /// Número de hilos
+    threads: usize,
+    /// Número de resultados tras el que se detiene
+    max_results: usize,
+    /// Límite de intentos
+    max_attempts: Option<u64>,
+    /// Límite de tiempo desde que empieza la búsqueda
+    max_time: Option<Duration>,
+    /// Momento límite
+    deadline: Option<Instant>,
+    /// Origen de las claves
+    key_source: Arc<dyn KeySource>,
+    /// Permite entregar claves de una fuente determinista
+    allow_deterministic_keys: bool,
+    /// Señal de cancelación de la búsqueda
+    cancel: CancelToken,
+    /// Intervalo de los informes de progreso
+    progress_interval: Option<Duration>,
+    /// Observador de la búsqueda
+    observer: Option<Arc<dyn SearchObserver>>,
+    /// Directorios donde guardar los resultados
+    output_dirs: Vec<PathBuf>,
+    /// Destinos propios de los resultados
+    sinks: Vec<Box<dyn ResultSink>>,
+}
+
+impl Default for Searcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Searcher {
+    /// Búsqueda vacía: un resultado, todas las CPUs y claves de `OsKeySource`
+    pub fn new() -> Self {
+        Self {
+            prefixes: Vec::new(),
+            suffixes: Vec::new(),
+            regexes: Vec::new(),
+            matchers: Vec::new(),
+            threads: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            max_results: 1,
+            max_attempts: None,
+            max_time: None,
+            deadline: None,
+            key_source: Arc::new(OsKeySource),
+            allow_deterministic_keys: false,
+            cancel: CancelToken::new(),
+            progress_interval: None,
+            observer: None,
+            output_dirs: Vec::new(),
+            sinks: Vec::new(),
+        }
+    }
+
+    /// Añade un prefijo (a-z y 2-7)
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Añade varios prefijos
+    pub fn prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.prefixes.extend(prefixes.into_iter().map(Into::into));
+        self
+    }
+
+    /// Añade un sufijo (antes de ".onion")
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffixes.push(suffix.into());
+        self
+    }
+
+    /// Añade una expresión regular sobre los 56 caracteres de la dirección
+    pub fn regex(mut self, pattern: impl Into<String>) -> Self {
+        self.regexes.push(pattern.into());
+        self
+    }
+
+    /// Añade un matcher propio
+    pub fn matcher(mut self, matcher: Arc<dyn Matcher>) -> Self {
+        self.matchers.push(matcher);
+        self
+    }
+
+    /// Número de hilos
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Número de resultados tras el que se detiene la búsqueda
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    /// Límite de intentos
+    pub fn max_attempts(mut self, max_attempts: u64) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Límite de tiempo, contado desde que empieza `run`
+    pub fn max_time(mut self, max_time: Duration) -> Self {
+        self.max_time = Some(max_time);
+        self
+    }
+
+    /// Momento en que debe detenerse la búsqueda
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Origen de las claves (por defecto, `OsKeySource`)
+    pub fn key_source(mut self, key_source: Arc<dyn KeySource>) -> Self {
+        self.key_source = key_source;
+        self
+    }
+
+    /// Permite entregar claves de una fuente determinista (inseguras)
+    pub fn allow_deterministic_keys(mut self, allow: bool) -> Self {
+        self.allow_deterministic_keys = allow;
+        self
+    }
+
+    /// Señal para cancelar la búsqueda desde otro hilo
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Intervalo de los informes de progreso al observador
+    pub fn progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = Some(interval);
+        self
+    }
+
+    /// Observador de progreso, resultados y final (progreso cada segundo
+    /// salvo que se indique `progress_interval`)
+    pub fn observer(mut self, observer: Arc<dyn SearchObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Guarda cada resultado en el directorio con los ficheros de Tor habituales
+    pub fn save_to(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dirs.push(output_dir.into());
+        self
+    }
+
+    /// Añade un destino propio de los resultados
+    pub fn sink(mut self, sink: impl ResultSink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Configuración equivalente, validando los patrones y los límites
+    ///
+    /// # Returns
+    ///
+    /// La configuración para `search_vanity`, o un error si no hay ningún
+    /// patrón posible o algún valor es inválido
+    pub fn config(&self) -> Result<Config> {
+        if self.threads == 0 {
+            return Err(VanityError::InvalidConfig(
+                "El número de hilos debe ser mayor que 0".to_string(),
+            ));
+        }
+        if self.max_results == 0 {
+            return Err(VanityError::InvalidConfig(
+                "El número máximo de resultados debe ser mayor que 0".to_string(),
+            ));
+        }
+
+        let mut matchers = self.matchers.clone();
+        if !self.prefixes.is_empty() {
+            matchers.push(Arc::new(
+                PrefixMatcher::new(&self.prefixes).map_err(VanityError::InvalidPrefix)?,
+            ));
+        }
+        if let Some(reason) = self
+            .suffixes
+            .iter()
+            .find_map(|suffix| explain_impossible_suffix(suffix).map(|r| (suffix, r)))
+        {
+            return Err(VanityError::InvalidConfig(format!(
+                "El sufijo '{}' nunca puede aparecer: {}",
+                reason.0, reason.1
+            )));
+        }
+        if !self.suffixes.is_empty() {
+            matchers.push(Arc::new(
+                SuffixMatcher::new(&self.suffixes).map_err(VanityError::InvalidConfig)?,
+            ));
+        }
+        for pattern in &self.regexes {
+            matchers.push(Arc::new(
+                RegexMatcher::new(pattern).map_err(VanityError::InvalidConfig)?,
+            ));
+        }
+
+        let matcher: Arc<dyn Matcher> = match matchers.len() {
+            0 => {
+                return Err(VanityError::InvalidConfig(
+                    "Falta un patrón: prefix, suffix, regex o matcher".to_string(),
+                ))
+            }
+            1 => matchers.remove(0),
+            _ => Arc::new(AnyMatcher::new(matchers)),
+        };
+
+        let progress_interval = self
+            .progress_interval
+            .or(self.observer.as_ref().map(|_| DEFAULT_PROGRESS_INTERVAL));
+
+        Ok(Config {
+            prefixes: self.prefixes.clone(),
+            matcher,
+            threads: self.threads,
+            max_results: self.max_results,
+            quotas: Vec::new(),
+            max_attempts: self.max_attempts,
+            deadline: self.deadline,
+            max_time: self.max_time,
+            checkpoint_interval: None,
+            resume: None,
+            key_source: Arc::clone(&self.key_source),
+            allow_deterministic_keys: self.allow_deterministic_keys,
+            report: progress_interval.map(|interval| ReportOptions {
+                interval,
+                style: ReportStyle::Silent,
+            }),
+            cancel: self.cancel.clone(),
+            observer: self.observer.clone(),
+            // Los destinos guardan las claves: no es un dry-run
+            output_dir: PathBuf::new(),
+            dry_run: false,
+        })
+    }
+
+    /// Ejecuta la búsqueda entregando cada resultado a los destinos
+    ///
+    /// # Returns
+    ///
+    /// Las estadísticas finales (con `stop_reason`), o el primer error de
+    /// configuración, de búsqueda o de un destino
+    pub fn run(mut self) -> Result<SearchStats> {
+        let config = self.config()?;
+        for output_dir in &self.output_dirs {
+            self.sinks
+                .push(Box::new(DirectorySink::new(output_dir, self.threads)?));
+        }
+
+        let sinks = &mut self.sinks;
+        search_vanity(&config, |result| {
+            sinks.iter_mut().try_for_each(|sink| sink.accept(&result))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_source::SeededKeySource;
+    use crate::reporter::ProgressReport;
+    use crate::types::StopReason;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Observador que cuenta los eventos recibidos
+    #[derive(Debug, Default)]
+    struct CountingObserver {
+        progress: AtomicUsize,
+        results: AtomicUsize,
+        finished: Mutex<Option<SearchStats>>,
+    }
+
+    impl SearchObserver for CountingObserver {
+        fn on_progress(&self, _report: &ProgressReport) {
+            self.progress.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_result(&self, _result: &VanityResult) {
+            self.results.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_finish(&self, stats: &SearchStats) {
+            *self.finished.lock().unwrap() = Some(stats.clone());
+        }
+    }
+
+    #[test]
+    fn test_searcher_sinks_and_observer() {
+        let temp_dir = TempDir::new().unwrap();
+        let observer = Arc::new(CountingObserver::default());
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let sink_results = Arc::clone(&collected);
+
+        let stats = Searcher::new()
+            .prefix("a")
+            .suffix("ad")
+            .threads(2)
+            .max_results(2)
+            .max_attempts(1_000_000) // Límite de seguridad
+            .observer(observer.clone())
+            .save_to(temp_dir.path())
+            .sink(move |result: &VanityResult| {
+                sink_results.lock().unwrap().push(result.address.clone());
+                Ok(())
+            })
+            .run()
+            .unwrap();
+
+        assert_eq!(stats.results_found, 2);
+        assert_eq!(stats.stop_reason, StopReason::GoalReached);
+        assert_eq!(observer.results.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            observer.finished.lock().unwrap().as_ref().unwrap().results_found,
+            2
+        );
+
+        let collected = collected.lock().unwrap();
+        assert_eq!(collected.len(), 2);
+        for address in collected.iter() {
+            assert!(address.starts_with('a') || address.ends_with("ad.onion"));
+            let body = address.trim_end_matches(".onion");
+            assert!(temp_dir.path().join(format!("{}_tor", body)).exists());
+        }
+    }
+
+    #[test]
+    fn test_searcher_progress_and_errors() {
+        // El observador recibe informes periódicos
+        let observer = Arc::new(CountingObserver::default());
+        let stats = Searcher::new()
+            .prefix("aaaaaaaa")
+            .threads(1)
+            .max_time(Duration::from_millis(300))
+            .progress_interval(Duration::from_millis(50))
+            .observer(observer.clone())
+            .run()
+            .unwrap();
+        assert_eq!(stats.stop_reason, StopReason::Deadline);
+        assert!(observer.progress.load(Ordering::Relaxed) >= 2);
+
+        // Un error del destino detiene la búsqueda y se devuelve
+        let outcome = Searcher::new()
+            .prefix("a")
+            .max_attempts(1_000_000)
+            .sink(|_: &VanityResult| Err(VanityError::KeyStorage("disco lleno".to_string())))
+            .run();
+        assert!(matches!(outcome, Err(VanityError::KeyStorage(_))));
+
+        // Configuraciones inválidas
+        assert!(Searcher::new().run().is_err());
+        assert!(Searcher::new().prefix("ABC").config().is_err());
+        assert!(Searcher::new().suffix("xa").config().is_err());
+        assert!(Searcher::new().prefix("a").threads(0).config().is_err());
+        let seeded = Searcher::new()
+            .prefix("a")
+            .key_source(Arc::new(SeededKeySource::new(1)));
+        assert!(seeded.config().unwrap().key_source.is_deterministic());
+        assert!(seeded.max_attempts(1_000_000).run().is_err());
+    }
+}
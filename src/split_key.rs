@@ -0,0 +1,431 @@
+use crate::error::{Result, VanityError};
+use crate::generator::{expand_secret_key, public_key_from_secret, KeyWalker};
+use crate::key_source::KeySource;
+use crate::matcher::{Candidate, Matcher, PrefixMatcher};
+use crate::types::VanityResult;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Versión del formato de los ficheros de clave dividida
+const SPLIT_VERSION: u32 = 1;
+
+/// Nombre del fichero donde un worker acumula los desplazamientos encontrados
+pub const OFFSETS_FILE: &str = "split-offsets.jsonl";
+
+/// Trabajo de búsqueda con clave dividida, para compartir con otra máquina
+///
+/// Contiene solo el punto público del solicitante: quien busca encuentra
+/// desplazamientos `b` tales que `A + b·B` tiene el prefijo, pero sin el
+/// secreto `a` no puede obtener la clave final `a + b`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitJob {
+    /// Versión del formato
+    pub version: u32,
+    /// Punto público del solicitante (hexadecimal, 32 bytes comprimidos)
+    pub public_key: String,
+    /// Prefijos a buscar
+    pub prefixes: Vec<String>,
+    /// Número de resultados pedidos
+    pub max_results: usize,
+    /// Fecha de creación (RFC 3339)
+    pub created_at: String,
+}
+
+/// Secreto del solicitante; nunca sale de su máquina
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitSecret {
+    /// Versión del formato
+    pub version: u32,
+    /// Punto público correspondiente (identifica el trabajo)
+    pub public_key: String,
+    /// Clave secreta expandida (hexadecimal, 64 bytes)
+    pub secret_key: String,
+}
+
+/// Desplazamiento encontrado por un worker para un trabajo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitOffset {
+    /// Punto público del trabajo
+    pub public_key: String,
+    /// Desplazamiento escalar (hexadecimal, 32 bytes)
+    pub offset: String,
+    /// Dirección .onion que resulta al combinarlo
+    pub address: String,
+    /// Prefijo que coincidió
+    pub matched_prefix: String,
+    /// Momento del hallazgo (RFC 3339)
+    pub found_at: String,
+}
+
+impl SplitJob {
+    /// Crea un par de claves nuevo y el trabajo para buscar sus desplazamientos
+    ///
+    /// El escalar secreto es "clamped" y con el bit 253 a cero; como los
+    /// desplazamientos son múltiplos de 8 menores que 2^253, la suma `a + b`
+    /// sigue siendo un escalar clamped válido para Tor sin reducir módulo ℓ.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefixes` - Prefijos a buscar (ya validados)
+    /// * `max_results` - Número de resultados pedidos
+    ///
+    /// # Returns
+    ///
+    /// El trabajo (para compartir) y el secreto (para guardar)
+    pub fn create(prefixes: &[String], max_results: usize) -> (Self, SplitSecret) {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        let mut secret = expand_secret_key(&seed);
+        secret[31] &= !0x20;
+
+        let public_key = hex::encode(public_key_from_secret(&secret));
+        let job = Self {
+            version: SPLIT_VERSION,
+            public_key: public_key.clone(),
+            prefixes: prefixes.to_vec(),
+            max_results,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let secret = SplitSecret {
+            version: SPLIT_VERSION,
+            public_key,
+            secret_key: hex::encode(secret),
+        };
+        (job, secret)
+    }
+
+    /// Carga un trabajo de un fichero JSON
+    pub fn load(path: &Path) -> Result<Self> {
+        let job: Self = read_json(path)?;
+        check_version(job.version)?;
+        Ok(job)
+    }
+
+    /// Guarda el trabajo en un fichero JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Fuente de claves que recorre desplazamientos sobre el punto del trabajo
+    pub fn key_source(&self) -> Result<SplitKeySource> {
+        SplitKeySource::new(&self.public_key)
+    }
+}
+
+impl SplitSecret {
+    /// Carga el secreto de un fichero JSON
+    pub fn load(path: &Path) -> Result<Self> {
+        let secret: Self = read_json(path)?;
+        check_version(secret.version)?;
+        Ok(secret)
+    }
+
+    /// Guarda el secreto sin sobrescribir uno existente (permisos 0600 en Unix)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(path).map_err(|e| {
+            VanityError::KeyStorage(format!(
+                "No se pudo crear el secreto {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl SplitOffset {
+    /// Extrae el desplazamiento de un resultado de búsqueda con `SplitKeySource`
+    ///
+    /// `private_key` de esos resultados contiene solo el desplazamiento.
+    pub fn from_result(job: &SplitJob, result: &VanityResult) -> Self {
+        Self {
+            public_key: job.public_key.clone(),
+            offset: hex::encode(&result.private_key[..32]),
+            address: result.address.clone(),
+            matched_prefix: result.matched_prefix.clone(),
+            found_at: result.timestamp.to_rfc3339(),
+        }
+    }
+
+    /// Añade el desplazamiento a un fichero JSON Lines
+    pub fn append(&self, path: &Path) -> Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Lee los desplazamientos de un fichero JSON Lines
+    pub fn load_all(path: &Path) -> Result<Vec<Self>> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            VanityError::InvalidConfig(format!("No se pudo leer {}: {}", path.display(), e))
+        })?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+}
+
+/// Combina el secreto del solicitante con un desplazamiento
+///
+/// # Arguments
+///
+/// * `job` - Trabajo del que sale el desplazamiento
+/// * `secret` - Secreto del solicitante
+/// * `offset` - Desplazamiento encontrado por el worker
+///
+/// # Returns
+///
+/// El resultado con la clave final, listo para `storage::save_result`, o un
+/// error si el desplazamiento es de otro trabajo, no da la dirección
+/// anunciada o esta no empieza por ninguno de los prefijos del trabajo
+pub fn combine(job: &SplitJob, secret: &SplitSecret, offset: &SplitOffset) -> Result<VanityResult> {
+    if secret.public_key != job.public_key {
+        return Err(VanityError::InvalidConfig(
+            "El secreto pertenece a otro trabajo".to_string(),
+        ));
+    }
+    if offset.public_key != secret.public_key {
+        return Err(VanityError::InvalidConfig(format!(
+            "El desplazamiento de {} pertenece a otro trabajo",
+            offset.address
+        )));
+    }
+
+    let mut private_key: [u8; 64] = decode_hex(&secret.secret_key, "clave secreta")?;
+    let offset_bytes: [u8; 32] = decode_hex(&offset.offset, "desplazamiento")?;
+    if offset_bytes[0] & 7 != 0 || offset_bytes[31] >= 0x20 {
+        return Err(VanityError::Crypto(
+            "El desplazamiento debe ser múltiplo de 8 y menor que 2^253".to_string(),
+        ));
+    }
+
+    // Suma de enteros de 256 bits: a < 2^254 + 2^253 y b < 2^253, sin acarreo final
+    let mut carry = 0u16;
+    for (byte, extra) in private_key[..32].iter_mut().zip(offset_bytes) {
+        let sum = *byte as u16 + extra as u16 + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+
+    let public_key = public_key_from_secret(&private_key);
+    let address = Candidate::new(&public_key).into_address();
+    if address != offset.address {
+        return Err(VanityError::Crypto(format!(
+            "El desplazamiento no produce {} (se obtuvo {})",
+            offset.address, address
+        )));
+    }
+
+    // El worker elige qué desplazamientos devuelve: la etiqueta sale de los
+    // prefijos del trabajo, no de lo que anuncia
+    let matcher = PrefixMatcher::new(&job.prefixes).map_err(VanityError::InvalidPrefix)?;
+    let Some(matched_prefix) = matcher.matches(&Candidate::new(&public_key)) else {
+        return Err(VanityError::Crypto(format!(
+            "{} no empieza por ningún prefijo del trabajo",
+            address
+        )));
+    };
+
+    let timestamp = chrono::DateTime::parse_from_rfc3339(&offset.found_at)
+        .map(|time| time.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|_| chrono::Utc::now());
+    Ok(VanityResult {
+        address,
+        matched_prefix,
+        dictionary_word: None,
+        private_key,
+        public_key,
+        timestamp,
+    })
+}
+
+/// Fuente de claves de la búsqueda con clave dividida
+///
+/// Cada worker parte de un desplazamiento aleatorio (múltiplo de 8, menor
+/// que 2^252) y recorre `A + b·B`; los resultados llevan en `private_key`
+/// solo el desplazamiento.
+#[derive(Debug, Clone)]
+pub struct SplitKeySource {
+    /// Punto público del solicitante en hexadecimal
+    public_key: String,
+    /// Punto público del solicitante
+    point: EdwardsPoint,
+}
+
+impl SplitKeySource {
+    /// Crea la fuente a partir del punto público en hexadecimal
+    pub fn new(public_key: &str) -> Result<Self> {
+        let bytes: [u8; 32] = decode_hex(public_key, "clave pública")?;
+        let point = CompressedEdwardsY(bytes)
+            .decompress()
+            .ok_or_else(|| VanityError::Crypto("La clave pública no es un punto válido".into()))?;
+
+        Ok(Self {
+            public_key: public_key.to_string(),
+            point,
+        })
+    }
+}
+
+impl KeySource for SplitKeySource {
+    fn name(&self) -> String {
+        format!("clave dividida ({}...)", &self.public_key[..16])
+    }
+
+    fn walker(&self, _worker: usize, _segment: u64, _start_step: u64) -> KeyWalker {
+        let mut offset = [0u8; 32];
+        OsRng.fill_bytes(&mut offset);
+        offset[0] &= 0xf8;
+        offset[31] &= 0x0f;
+        KeyWalker::from_offset(&self.point, offset)
+    }
+}
+
+/// Comprueba la versión de un fichero de clave dividida
+fn check_version(version: u32) -> Result<()> {
+    if version != SPLIT_VERSION {
+        return Err(VanityError::InvalidConfig(format!(
+            "Versión de fichero de clave dividida no soportada: {}",
+            version
+        )));
+    }
+    Ok(())
+}
+
+/// Lee un fichero JSON
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        VanityError::InvalidConfig(format!("No se pudo leer {}: {}", path.display(), e))
+    })?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Decodifica un valor hexadecimal de longitud fija
+fn decode_hex<const N: usize>(value: &str, what: &str) -> Result<[u8; N]> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| VanityError::Crypto(format!("{} mal formada: se esperaban {} bytes", what, N)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::search_vanity;
+    use crate::types::Config;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Busca un desplazamiento para el trabajo como lo haría un worker
+    fn find_offset(job: &SplitJob) -> SplitOffset {
+        let prefixes: Vec<&str> = job.prefixes.iter().map(String::as_str).collect();
+        let config = Config {
+            threads: 1,
+            max_attempts: Some(1_000_000), // Límite de seguridad
+            key_source: Arc::new(job.key_source().unwrap()),
+            ..Config::for_test(&prefixes)
+        };
+
+        let mut offsets = Vec::new();
+        search_vanity(&config, |result| {
+            offsets.push(SplitOffset::from_result(job, &result));
+            Ok(())
+        })
+        .unwrap();
+        offsets.remove(0)
+    }
+
+    #[test]
+    fn test_split_key_combine() {
+        use ed25519_dalek::hazmat::{raw_sign, ExpandedSecretKey};
+        use ed25519_dalek::{Verifier, VerifyingKey};
+
+        let (job, secret) = SplitJob::create(&["ab".to_string()], 1);
+        let offset = find_offset(&job);
+        assert!(offset.address.starts_with("ab"));
+
+        // El worker solo conoce el desplazamiento, que no es la clave final
+        let result = combine(&job, &secret, &offset).unwrap();
+        assert_eq!(result.address, offset.address);
+        assert_ne!(hex::encode(&result.private_key[..32]), offset.offset);
+
+        // La clave final es un escalar clamped que firma como la clave pública
+        let expanded = ExpandedSecretKey::from_bytes(&result.private_key);
+        let verifying_key = VerifyingKey::from_bytes(&result.public_key).unwrap();
+        assert_eq!(VerifyingKey::from(&expanded), verifying_key);
+        let signature = raw_sign::<sha2::Sha512>(&expanded, b"torge-forge", &verifying_key);
+        assert!(verifying_key.verify(b"torge-forge", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_split_key_combine_rejects_foreign_offsets() {
+        let (job, secret) = SplitJob::create(&["a".to_string()], 1);
+        let offset = find_offset(&job);
+
+        // Desplazamiento de otro trabajo
+        let (other_job, other_secret) = SplitJob::create(&["a".to_string()], 1);
+        assert!(combine(&other_job, &other_secret, &offset).is_err());
+        assert!(combine(&job, &other_secret, &offset).is_err());
+
+        // Desplazamiento que no da la dirección anunciada
+        let tampered = SplitOffset {
+            offset: hex::encode([8u8; 32].map(|byte| byte & 0x18)),
+            ..offset.clone()
+        };
+        assert!(combine(&job, &secret, &tampered).is_err());
+
+        // Desplazamiento válido cuya dirección no tiene ningún prefijo del
+        // trabajo (p. ej. de una búsqueda con otros prefijos)
+        let first = &offset.address[..1];
+        let other = if first == "b" { "c" } else { "b" };
+        let narrowed = SplitJob {
+            prefixes: vec![other.to_string()],
+            ..job.clone()
+        };
+        let err = combine(&narrowed, &secret, &offset).unwrap_err();
+        assert!(err.to_string().contains("ningún prefijo"), "{}", err);
+    }
+
+    #[test]
+    fn test_split_key_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let job_path = temp_dir.path().join("job.json");
+        let secret_path = temp_dir.path().join("secret.json");
+        let offsets_path = temp_dir.path().join(OFFSETS_FILE);
+
+        let (job, secret) = SplitJob::create(&["a".to_string()], 2);
+        job.save(&job_path).unwrap();
+        secret.save(&secret_path).unwrap();
+        assert_eq!(SplitJob::load(&job_path).unwrap(), job);
+        assert_eq!(SplitSecret::load(&secret_path).unwrap(), secret);
+
+        // El secreto nunca se sobrescribe
+        assert!(secret.save(&secret_path).is_err());
+
+        let offset = find_offset(&job);
+        offset.append(&offsets_path).unwrap();
+        offset.append(&offsets_path).unwrap();
+        assert_eq!(SplitOffset::load_all(&offsets_path).unwrap(), vec![offset.clone(), offset]);
+    }
+}
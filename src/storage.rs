@@ -6,7 +6,7 @@ use std::path::{Path, PathBuf};
 /// Guarda un resultado de búsqueda en disco
 /// 
 /// Crea los siguientes archivos:
-/// 1. `<address>_private.key` - Clave privada en formato hexadecimal (backup)
+/// 1. `<address>_private.key` - Clave secreta expandida en formato hexadecimal (backup)
 /// 2. `<address>_tor/hs_ed25519_secret_key` - Clave en formato binario de Tor (listo para usar)
 /// 3. `<address>_tor/hostname` - Dirección .onion (formato Tor)
 /// 4. `<address>_metadata.json` - Metadatos en formato JSON
@@ -47,7 +47,7 @@ pub fn save_result(
         VanityError::DirectoryCreation(format!("No se pudo crear directorio Tor {}: {}", tor_dir.display(), e))
     })?;
 
-    // Guardar clave privada en formato hexadecimal (backup)
+    // Guardar clave secreta expandida en formato hexadecimal (backup)
     let private_key_hex = hex::encode(result.private_key);
     fs::write(&private_key_path, &private_key_hex).map_err(|e| {
         VanityError::KeyStorage(format!(
@@ -70,6 +70,7 @@ pub fn save_result(
     let metadata = KeyMetadata {
         onion_address: result.address.clone(),
         matched_prefix: result.matched_prefix.clone(),
+        dictionary_word: result.dictionary_word.clone(),
         generated_at: result.timestamp.to_rfc3339(),
         threads_used,
         public_key_hex,
@@ -100,29 +101,17 @@ pub fn save_result(
 /// Guarda la clave privada en formato binario de Tor
 /// 
 /// Formato correcto de Tor (96 bytes total):
-/// - Bytes 0-31: "== ed25519v1-secret: type0 =="
-/// - Bytes 32-34: 0x00 0x00 0x00
-/// - Bytes 35-98: 64 bytes de clave expandida (SHA-512 de la clave privada)
+/// - Bytes 0-28: "== ed25519v1-secret: type0 =="
+/// - Bytes 29-31: 0x00 0x00 0x00
+/// - Bytes 32-95: 64 bytes de clave expandida (escalar || prefijo de nonce)
 /// 
 /// # Arguments
 /// 
-/// * `private_key` - Clave privada de 32 bytes
+/// * `expanded_key` - Clave secreta expandida de 64 bytes
 /// * `tor_dir` - Directorio donde guardar el archivo
-fn save_tor_secret_key(private_key: &[u8; 32], tor_dir: &Path) -> Result<()> {
+fn save_tor_secret_key(expanded_key: &[u8; 64], tor_dir: &Path) -> Result<()> {
     let secret_key_path = tor_dir.join("hs_ed25519_secret_key");
     
-    // Expandir la clave privada usando SHA-512 (estándar, no SHA3)
-    // Tor usa la "expanded" secret key que es el hash SHA-512 de la clave privada
-    use sha2::{Sha512, Digest};
-    let mut hasher = Sha512::new();
-    hasher.update(private_key);
-    let mut expanded_key = hasher.finalize();
-    
-    // Aplicar clamping según especificación Ed25519
-    expanded_key[0] &= 248;
-    expanded_key[31] &= 127;
-    expanded_key[31] |= 64;
-    
     // Crear el archivo en formato binario de Tor
     let mut content = Vec::new();
     
@@ -133,7 +122,7 @@ fn save_tor_secret_key(private_key: &[u8; 32], tor_dir: &Path) -> Result<()> {
     content.extend_from_slice(&[0u8; 3]);
     
     // 64 bytes de clave expandida
-    content.extend_from_slice(&expanded_key[..]);
+    content.extend_from_slice(expanded_key);
     
     fs::write(&secret_key_path, content).map_err(|e| {
         VanityError::KeyStorage(format!(
@@ -197,7 +186,7 @@ ARCHIVOS POR CADA DIRECCIÓN:
 ----------------------------
 
 1. <address>_private.key
-   - Clave privada Ed25519 en formato hexadecimal (64 caracteres)
+   - Clave secreta Ed25519 expandida en formato hexadecimal (128 caracteres)
    - MANTENER SEGURA Y PRIVADA
    - Necesaria para configurar el servicio oculto de Tor
 
@@ -267,7 +256,8 @@ mod tests {
         let result = VanityResult {
             address: "test1234567890abcdefghijklmnopqrstuvwxyz234567abcdefgh.onion".to_string(),
             matched_prefix: "test".to_string(),
-            private_key: [1u8; 32],
+            dictionary_word: None,
+            private_key: [1u8; 64],
             public_key: [2u8; 32],
             timestamp: Utc::now(),
         };
@@ -280,8 +270,15 @@ mod tests {
         assert!(output_dir.join("test1234567890abcdefghijklmnopqrstuvwxyz234567abcdefgh_hostname.txt").exists());
 
         // Verificar contenido de la clave privada
-        let private_key_content = fs::read_to_string(&saved_path).unwrap();
-        assert_eq!(private_key_content.len(), 64); // 32 bytes en hex = 64 caracteres
+        let private_key_path =
+            output_dir.join("test1234567890abcdefghijklmnopqrstuvwxyz234567abcdefgh_private.key");
+        let private_key_content = fs::read_to_string(private_key_path).unwrap();
+        assert_eq!(private_key_content.len(), 128); // 64 bytes en hex = 128 caracteres
+
+        // Verificar el archivo binario de Tor
+        let tor_key = fs::read(saved_path.join("hs_ed25519_secret_key")).unwrap();
+        assert_eq!(tor_key.len(), 96);
+        assert_eq!(&tor_key[32..], &[1u8; 64][..]);
     }
 
     #[test]
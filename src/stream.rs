@@ -0,0 +1,227 @@
+//! Búsqueda como `Stream` para servicios asíncronos (feature `async`)
+//!
+//! `search_vanity` bloquea el hilo que la llama hasta terminar, algo que no
+//! conviene dentro de un runtime asíncrono. `search_stream` lanza la búsqueda en
+//! un hilo propio (los workers, en el pool de la búsqueda) y entrega sus eventos
+//! por un canal: el runtime solo espera mensajes.
+
+use crate::error::Result;
+use crate::reporter::{ProgressReport, SearchObserver};
+use crate::search::search_vanity;
+use crate::types::{CancelToken, Config, SearchStats, VanityResult};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::stream::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Evento de una búsqueda lanzada con `search_stream`
+#[derive(Debug)]
+pub enum SearchEvent {
+    /// Informe periódico (solo si la configuración tiene `report`)
+    Progress(ProgressReport),
+    /// Resultado aceptado
+    Found(VanityResult),
+    /// Último evento: estadísticas finales o el error de la búsqueda
+    Finished(Result<SearchStats>),
+}
+
+impl SearchEvent {
+    /// Resultado que lleva el evento, si es `Found` (útil con `filter_map`)
+    pub fn into_result(self) -> Option<VanityResult> {
+        match self {
+            SearchEvent::Found(result) => Some(result),
+            _ => None,
+        }
+    }
+}
+
+/// Observador que reenvía los informes al stream y avisa al de la configuración
+#[derive(Debug)]
+struct StreamObserver {
+    /// Canal hacia el stream
+    events: UnboundedSender<SearchEvent>,
+    /// Observador original de la configuración
+    inner: Option<Arc<dyn SearchObserver>>,
+}
+
+impl SearchObserver for StreamObserver {
+    fn on_progress(&self, report: &ProgressReport) {
+        let _ = self.events.unbounded_send(SearchEvent::Progress(report.clone()));
+        if let Some(inner) = &self.inner {
+            inner.on_progress(report);
+        }
+    }
+
+    fn on_result(&self, result: &VanityResult) {
+        if let Some(inner) = &self.inner {
+            inner.on_result(result);
+        }
+    }
+
+    fn on_finish(&self, stats: &SearchStats) {
+        if let Some(inner) = &self.inner {
+            inner.on_finish(stats);
+        }
+    }
+}
+
+/// Lanza la búsqueda en segundo plano y entrega sus eventos como un `Stream`
+///
+/// La búsqueda atiende a `config.cancel` con una señal derivada (ver
+/// `CancelToken::child`): soltar el stream la cancela sin bloquear al runtime
+/// ni afectar a otras búsquedas que compartan la señal. El stream termina tras
+/// `SearchEvent::Finished`.
+///
+/// # Arguments
+///
+/// * `config` - Configuración de la búsqueda
+///
+/// # Returns
+///
+/// Stream con el progreso, los resultados y el final de la búsqueda
+pub fn search_stream(config: &Config) -> SearchStream {
+    let (events, receiver) = unbounded();
+    let cancel = config.cancel.child();
+    let config = Config {
+        cancel: cancel.clone(),
+        observer: Some(Arc::new(StreamObserver {
+            events: events.clone(),
+            inner: config.observer.clone(),
+        })),
+        ..config.clone()
+    };
+
+    std::thread::spawn(move || {
+        let outcome = search_vanity(&config, |result| {
+            let _ = events.unbounded_send(SearchEvent::Found(result));
+            Ok(())
+        });
+        let _ = events.unbounded_send(SearchEvent::Finished(outcome));
+    });
+
+    SearchStream { receiver, cancel }
+}
+
+/// Eventos de una búsqueda lanzada con `search_stream`
+///
+/// Al soltarse cancela la búsqueda; los workers paran tras su lote en curso.
+pub struct SearchStream {
+    /// Eventos enviados por el hilo de la búsqueda
+    receiver: UnboundedReceiver<SearchEvent>,
+    /// Señal propia de esta búsqueda
+    cancel: CancelToken,
+}
+
+impl SearchStream {
+    /// Pide que la búsqueda se detenga sin soltar el stream
+    ///
+    /// Los resultados ya encontrados siguen llegando antes de `Finished`.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Stream for SearchStream {
+    type Item = SearchEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SearchEvent>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl Drop for SearchStream {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::PrefixMatcher;
+    use crate::reporter::{ReportOptions, ReportStyle};
+    use crate::types::StopReason;
+    use futures::executor::block_on;
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+
+    fn config(max_results: usize) -> Config {
+        Config {
+            max_results,
+            ..Config::for_test(&["a"])
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct FinishFlag(AtomicBool);
+
+    impl SearchObserver for FinishFlag {
+        fn on_finish(&self, _stats: &SearchStats) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_search_stream_events() {
+        let events: Vec<SearchEvent> = block_on(search_stream(&config(2)).collect());
+        let found = events
+            .iter()
+            .filter(|e| matches!(e, SearchEvent::Found(_)))
+            .count();
+        assert_eq!(found, 2);
+        match events.last() {
+            Some(SearchEvent::Finished(Ok(stats))) => {
+                assert_eq!(stats.stop_reason, StopReason::GoalReached);
+                assert_eq!(stats.results_found, 2);
+            }
+            other => panic!("último evento inesperado: {:?}", other),
+        }
+
+        // Un patrón inalcanzable con límite de tiempo: solo progreso y el final
+        let config = Config {
+            matcher: Arc::new(PrefixMatcher::new(&["aaaaaaaaaa".to_string()]).unwrap()),
+            max_time: Some(Duration::from_millis(200)),
+            report: Some(ReportOptions {
+                interval: Duration::from_millis(20),
+                style: ReportStyle::Silent,
+            }),
+            ..config(1)
+        };
+        let events: Vec<SearchEvent> = block_on(search_stream(&config).collect());
+        assert!(events.iter().any(|e| matches!(e, SearchEvent::Progress(_))));
+        assert!(matches!(
+            events.last(),
+            Some(SearchEvent::Finished(Ok(SearchStats {
+                stop_reason: StopReason::Deadline,
+                ..
+            })))
+        ));
+    }
+
+    #[test]
+    fn test_search_stream_drop_cancels() {
+        let flag = Arc::new(FinishFlag::default());
+        let config = Config {
+            observer: Some(flag.clone()),
+            ..config(usize::MAX)
+        };
+
+        let results: Vec<VanityResult> = block_on(
+            search_stream(&config)
+                .filter_map(|event| async move { event.into_result() })
+                .take(3)
+                .collect(),
+        );
+        assert_eq!(results.len(), 3);
+
+        // Soltado el stream, la búsqueda sin límites termina por sí sola
+        let start = Instant::now();
+        while !flag.0.load(Ordering::Relaxed) {
+            assert!(start.elapsed() < Duration::from_secs(10), "la búsqueda no se detuvo");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!config.cancel.is_cancelled());
+    }
+}
@@ -1,17 +1,48 @@
+use crate::checkpoint::Checkpoint;
+use crate::key_source::KeySource;
+use crate::matcher::Matcher;
+use crate::reporter::{ReportOptions, SearchObserver};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Configuración principal de la aplicación
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Prefijos a buscar (en minúsculas, alfabeto base32)
     pub prefixes: Vec<String>,
+    /// Regla que deben cumplir las direcciones (por defecto, `PrefixMatcher`)
+    pub matcher: Arc<dyn Matcher>,
     /// Número de hilos a utilizar
     pub threads: usize,
-    /// Número máximo de resultados a generar
+    /// Número máximo de resultados a generar; con cupos, el máximo que
+    /// comparten los patrones sin cupo propio
     pub max_results: usize,
+    /// Cupos de resultados por patrón; si hay alguno, la búsqueda termina
+    /// cuando todos están cubiertos
+    pub quotas: Vec<Quota>,
     /// Número máximo de intentos antes de detenerse (None = ilimitado)
     pub max_attempts: Option<u64>,
+    /// Momento a partir del cual se detiene la búsqueda (None = sin límite)
+    pub deadline: Option<Instant>,
+    /// Tiempo máximo desde que empieza la búsqueda (None = sin límite)
+    pub max_time: Option<Duration>,
+    /// Cada cuánto se escribe el checkpoint en `output_dir` (None = nunca)
+    pub checkpoint_interval: Option<Duration>,
+    /// Checkpoint desde el que continuar una búsqueda anterior
+    pub resume: Option<Checkpoint>,
+    /// Origen de las claves (por defecto, `OsKeySource`)
+    pub key_source: Arc<dyn KeySource>,
+    /// Permite guardar claves de una fuente determinista (inseguras)
+    pub allow_deterministic_keys: bool,
+    /// Informe periódico del progreso durante la búsqueda (None = sin informes)
+    pub report: Option<ReportOptions>,
+    /// Señal de cancelación que comprueban los workers (p. ej. desde Ctrl+C)
+    pub cancel: CancelToken,
+    /// Observador de progreso, resultados y final de la búsqueda
+    pub observer: Option<Arc<dyn SearchObserver>>,
     /// Directorio de salida para guardar las claves
     pub output_dir: PathBuf,
     /// Modo dry-run (no guardar en disco)
@@ -19,6 +50,16 @@ pub struct Config {
 }
 
 impl Config {
+    /// Momento en que debe detenerse una búsqueda que empieza en `start`: el
+    /// primero entre `deadline` y `start + max_time`
+    pub fn deadline_from(&self, start: Instant) -> Option<Instant> {
+        let by_time = self.max_time.map(|max_time| start + max_time);
+        match (self.deadline, by_time) {
+            (Some(deadline), Some(by_time)) => Some(deadline.min(by_time)),
+            (deadline, by_time) => deadline.or(by_time),
+        }
+    }
+
     /// Valida que los prefijos sean válidos para direcciones .onion v3
     /// El alfabeto base32 válido es: a-z y 2-7
     pub fn validate_prefixes(&self) -> Result<(), String> {
@@ -40,6 +81,56 @@ impl Config {
     }
 }
 
+#[cfg(test)]
+impl Config {
+    /// Configuración de prueba: los prefijos con dos hilos, un resultado, sin
+    /// límites ni informes y sin guardar nada (la de `Searcher` en dry-run)
+    pub(crate) fn for_test(prefixes: &[&str]) -> Self {
+        let searcher = crate::searcher::Searcher::new()
+            .prefixes(prefixes.iter().copied())
+            .threads(2);
+        Self {
+            dry_run: true,
+            ..searcher.config().unwrap()
+        }
+    }
+}
+
+/// Cupo de resultados para un patrón
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quota {
+    /// Etiqueta del patrón (la que devuelve el matcher, p. ej. el prefijo)
+    pub label: String,
+    /// Número de resultados buscados para el patrón
+    pub target: usize,
+}
+
+impl std::str::FromStr for Quota {
+    type Err = String;
+
+    /// Interpreta un cupo con formato `patrón:n` (por ejemplo `shop:3`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, target) = s
+            .rsplit_once(':')
+            .ok_or_else(|| format!("Cupo '{}' inválido: use el formato patrón:n", s))?;
+        let target: usize = target
+            .parse()
+            .map_err(|_| format!("Cupo '{}' inválido: '{}' no es un número", s, target))?;
+
+        if label.is_empty() {
+            return Err(format!("Cupo '{}' inválido: falta el patrón", s));
+        }
+        if target == 0 {
+            return Err(format!("Cupo '{}' inválido: debe ser mayor que 0", s));
+        }
+
+        Ok(Self {
+            label: label.to_string(),
+            target,
+        })
+    }
+}
+
 /// Resultado de una búsqueda exitosa
 #[derive(Debug, Clone)]
 pub struct VanityResult {
@@ -47,8 +138,10 @@ pub struct VanityResult {
     pub address: String,
     /// Prefijo que coincidió
     pub matched_prefix: String,
-    /// Clave privada Ed25519 (32 bytes)
-    pub private_key: [u8; 32],
+    /// Palabra de diccionario que coincidió, tal como aparece en el diccionario
+    pub dictionary_word: Option<String>,
+    /// Clave secreta Ed25519 expandida (escalar || prefijo de nonce, 64 bytes)
+    pub private_key: [u8; 64],
     /// Clave pública Ed25519 (32 bytes)
     pub public_key: [u8; 32],
     /// Timestamp de generación
@@ -62,6 +155,9 @@ pub struct KeyMetadata {
     pub onion_address: String,
     /// Prefijo usado para la búsqueda
     pub matched_prefix: String,
+    /// Palabra de diccionario que coincidió (solo en modo diccionario)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dictionary_word: Option<String>,
     /// Fecha y hora de generación (ISO 8601)
     pub generated_at: String,
     /// Número de hilos utilizados
@@ -70,6 +166,58 @@ pub struct KeyMetadata {
     pub public_key_hex: String,
 }
 
+/// Señal de cancelación compartida entre quien lanza la búsqueda y los workers
+///
+/// Los clones comparten el mismo estado: basta con cancelar uno.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    /// Estado propio de la señal
+    flag: Arc<AtomicBool>,
+    /// Señal de la que depende (ver `child`)
+    parent: Option<Arc<CancelToken>>,
+}
+
+impl CancelToken {
+    /// Crea una señal sin cancelar
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Señal que se cancela con esta, pero que puede cancelarse sola sin
+    /// afectar a esta
+    pub fn child(&self) -> Self {
+        Self {
+            flag: Arc::default(),
+            parent: Some(Arc::new(self.clone())),
+        }
+    }
+
+    /// Pide que la búsqueda se detenga
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Indica si se pidió detener la búsqueda (aquí o en la señal de la que depende)
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+            || self.parent.as_ref().is_some_and(|parent| parent.is_cancelled())
+    }
+}
+
+/// Motivo por el que terminó una búsqueda
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StopReason {
+    /// Se alcanzó `max_results` o se cubrieron todos los cupos
+    #[default]
+    GoalReached,
+    /// Se alcanzó `max_attempts`
+    MaxAttempts,
+    /// Se alcanzó el límite de tiempo (`deadline` o `max_time`)
+    Deadline,
+    /// Se canceló con el `CancelToken`
+    Cancelled,
+}
+
 /// Estadísticas de la búsqueda
 #[derive(Debug, Clone, Default)]
 pub struct SearchStats {
@@ -79,6 +227,28 @@ pub struct SearchStats {
     pub results_found: usize,
     /// Tiempo transcurrido en segundos
     pub elapsed_seconds: f64,
+    /// Progreso por patrón (etiquetas con cupo y etiquetas encontradas)
+    pub patterns: Vec<PatternStats>,
+    /// Motivo por el que terminó (en informes intermedios, `GoalReached`)
+    pub stop_reason: StopReason,
+}
+
+/// Progreso de un patrón durante la búsqueda
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternStats {
+    /// Etiqueta del patrón
+    pub label: String,
+    /// Resultados aceptados para el patrón
+    pub found: usize,
+    /// Cupo del patrón, si lo tiene
+    pub quota: Option<usize>,
+}
+
+impl PatternStats {
+    /// Indica si el cupo del patrón está cubierto
+    pub fn is_filled(&self) -> bool {
+        self.quota.is_some_and(|quota| self.found >= quota)
+    }
 }
 
 impl SearchStats {
@@ -1,6 +1,14 @@
-use crate::types::Config;
+use vanity_onion_v3::bench::{machine_rate, ASSUMED_RATE};
+use vanity_onion_v3::reporter::luck_summary;
+use vanity_onion_v3::matcher::{Matcher, PrefixMatcher, RegexMatcher};
+use vanity_onion_v3::search::{parse_duration, time_for_confidence, ETA_CONFIDENCES};
+use vanity_onion_v3::error::Result;
+use vanity_onion_v3::searcher::Searcher;
+use vanity_onion_v3::types::Config;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Muestra el banner de Torge Forge
 pub fn show_banner() {
@@ -66,6 +74,19 @@ pub fn read_number(prompt: &str, default: usize) -> usize {
     }
 }
 
+/// Lee una duración del usuario, repitiendo la pregunta hasta que sea válida
+pub fn read_duration(prompt: &str) -> Duration {
+    loop {
+        print!("{}: ", prompt);
+        io::stdout().flush().unwrap();
+
+        match parse_duration(&read_line()) {
+            Ok(duration) => return duration,
+            Err(e) => println!("   ❌ {}", e),
+        }
+    }
+}
+
 /// Lee una confirmación (s/n)
 pub fn read_confirmation(prompt: &str) -> bool {
     print!("{} [s/n]: ", prompt);
@@ -125,6 +146,38 @@ pub fn get_prefixes() -> Vec<String> {
     prefixes
 }
 
+/// Solicita una expresión regular al usuario hasta que sea válida
+pub fn get_regex() -> RegexMatcher {
+    println!("\n╔═══════════════════════════════════════════════════════════════╗");
+    println!("║                 CONFIGURAR EXPRESIÓN REGULAR                  ║");
+    println!("╚═══════════════════════════════════════════════════════════════╝");
+    println!("\n📝 El patrón se aplica a los 56 caracteres de la dirección (a-z y 2-7)");
+    println!("   Ejemplo: ^(shop|store)[a-z]{{2}}");
+    println!("⚠️  Las direcciones v3 siempre terminan en 'd'\n");
+
+    loop {
+        print!("Ingresa la expresión regular: ");
+        io::stdout().flush().unwrap();
+
+        let pattern = read_line();
+        if pattern.is_empty() {
+            println!("❌ Debes ingresar una expresión regular");
+            continue;
+        }
+
+        match RegexMatcher::new(&pattern) {
+            Ok(matcher) => {
+                println!(
+                    "✅ Expresión regular aceptada (probabilidad: 1 en {:.0})",
+                    1.0 / matcher.probability()
+                );
+                return matcher;
+            }
+            Err(e) => println!("❌ {}", e),
+        }
+    }
+}
+
 /// Valida que un prefijo sea válido (solo a-z y 2-7)
 fn is_valid_prefix(prefix: &str) -> bool {
     if prefix.is_empty() {
@@ -135,13 +188,20 @@ fn is_valid_prefix(prefix: &str) -> bool {
 }
 
 /// Configuración interactiva completa
-pub fn interactive_config() -> Config {
+///
+/// Falla si algún valor introducido es inválido (por ejemplo 0 hilos)
+pub fn interactive_config() -> Result<Config> {
     show_banner();
     
     println!("🎯 Configuración de generación de dirección .onion vanity\n");
     
-    // Obtener prefijos
-    let prefixes = get_prefixes();
+    // Obtener prefijos o una expresión regular
+    let regex = if read_confirmation("🔣 ¿Buscar con una expresión regular en lugar de prefijos?") {
+        Some(get_regex())
+    } else {
+        None
+    };
+    let prefixes = if regex.is_none() { get_prefixes() } else { Vec::new() };
     
     println!("\n╔═══════════════════════════════════════════════════════════════╗");
     println!("║                  CONFIGURACIÓN DE RENDIMIENTO                 ║");
@@ -163,6 +223,13 @@ pub fn interactive_config() -> Config {
     } else {
         None
     };
+
+    // Límite de tiempo (cuenta desde que empieza la búsqueda)
+    let max_time = if read_confirmation("\n⏰ ¿Establecer límite de tiempo?") {
+        Some(read_duration("   Tiempo máximo (ej: 6h, 90m, 1h30m)"))
+    } else {
+        None
+    };
     
     println!("\n╔═══════════════════════════════════════════════════════════════╗");
     println!("║                  CONFIGURACIÓN DE SALIDA                      ║");
@@ -186,7 +253,10 @@ pub fn interactive_config() -> Config {
     println!("║                    RESUMEN DE CONFIGURACIÓN                   ║");
     println!("╠═══════════════════════════════════════════════════════════════╣");
     println!("║                                                               ║");
-    println!("║  📝 Prefijos: {:45} ║", format!("{}", prefixes.join(", ")));
+    match &regex {
+        Some(matcher) => println!("║  🔣 Patrón: {:47} ║", matcher.name()),
+        None => println!("║  📝 Prefijos: {:45} ║", format!("{}", prefixes.join(", "))),
+    }
     println!("║  💻 Hilos: {:48} ║", threads);
     println!("║  🎯 Resultados máximos: {:36} ║", max_results);
     if let Some(attempts) = max_attempts {
@@ -194,6 +264,9 @@ pub fn interactive_config() -> Config {
     } else {
         println!("║  ⏱️  Intentos máximos: {:37} ║", "Ilimitado");
     }
+    if let Some(max_time) = max_time {
+        println!("║  ⏰ Tiempo máximo: {:41} ║", format_duration(max_time.as_secs_f64()));
+    }
     println!("║  📁 Directorio: {:44} ║", output_dir.display());
     println!("║  🔍 Modo dry-run: {:40} ║", if dry_run { "Sí" } else { "No" });
     println!("║                                                               ║");
@@ -204,14 +277,31 @@ pub fn interactive_config() -> Config {
         std::process::exit(0);
     }
     
-    Config {
+    // Los prefijos ya se validaron en get_prefixes
+    let matcher: Arc<dyn Matcher> = match regex {
+        Some(matcher) => Arc::new(matcher),
+        None => Arc::new(
+            PrefixMatcher::new(&prefixes).expect("prefijos validados en get_prefixes"),
+        ),
+    };
+
+    let mut searcher = Searcher::new()
+        .matcher(matcher)
+        .threads(threads)
+        .max_results(max_results);
+    if let Some(max_attempts) = max_attempts {
+        searcher = searcher.max_attempts(max_attempts);
+    }
+    if let Some(max_time) = max_time {
+        searcher = searcher.max_time(max_time);
+    }
+
+    Ok(Config {
         prefixes,
-        threads,
-        max_results,
-        max_attempts,
         output_dir,
         dry_run,
-    }
+        ..searcher.config()?
+    })
 }
 
 /// Muestra información y ayuda
@@ -280,15 +370,40 @@ pub fn show_time_estimation() {
     let probability = 1.0 / 32f64.powi(len as i32);
     let expected_attempts = 1.0 / probability;
     
-    // Asumir ~300k intentos/s en hardware moderno
-    let rate = 300_000.0;
+    // Tasa calibrada con `bench` para todos los hilos, o una tasa supuesta
+    let threads = num_cpus::get();
+    let calibrated = machine_rate(threads);
+    let rate = calibrated.unwrap_or(ASSUMED_RATE);
     let seconds = expected_attempts / rate;
     
     println!("\n📊 Estimación para prefijo '{}':", prefix);
     println!("   • Longitud: {} caracteres", len);
     println!("   • Probabilidad: 1 en {:.0}", 1.0 / probability);
     println!("   • Intentos esperados: {:.0}", expected_attempts);
-    println!("   • Tiempo estimado (300k intentos/s): {}", format_duration(seconds));
+    if calibrated.is_some() {
+        println!(
+            "   • Tiempo estimado ({:.0} intentos/s con {} hilos, calibrado): {}",
+            rate,
+            threads,
+            format_duration(seconds)
+        );
+    } else {
+        println!(
+            "   • Tiempo estimado (supuestos {:.0} intentos/s): {}",
+            rate,
+            format_duration(seconds)
+        );
+        println!("   💡 Mide esta máquina con: torge-forge-cli bench");
+    }
+
+    // El tiempo real varía mucho: tiempo hasta cada probabilidad de éxito
+    for confidence in ETA_CONFIDENCES {
+        println!(
+            "   • {:.0}% de probabilidad en: {}",
+            confidence * 100.0,
+            format_duration(time_for_confidence(probability, confidence, rate))
+        );
+    }
     
     if len >= 6 {
         println!("\n⚠️  ADVERTENCIA: Este prefijo puede tomar mucho tiempo");
@@ -365,7 +480,14 @@ pub fn show_result_found(prefix: &str, address: &str, count: usize, total: usize
 }
 
 /// Muestra estadísticas finales
-pub fn show_final_stats(results: usize, attempts: u64, elapsed: f64, output_dir: &str, dry_run: bool) {
+pub fn show_final_stats(
+    results: usize,
+    attempts: u64,
+    elapsed: f64,
+    probability: f64,
+    output_dir: &str,
+    dry_run: bool,
+) {
     println!("\n╔═══════════════════════════════════════════════════════════════╗");
     println!("║                    BÚSQUEDA COMPLETADA                        ║");
     println!("╚═══════════════════════════════════════════════════════════════╝\n");
@@ -379,6 +501,9 @@ pub fn show_final_stats(results: usize, attempts: u64, elapsed: f64, output_dir:
     if results > 0 {
         println!("   • Promedio por resultado: {:.0} intentos", attempts as f64 / results as f64);
     }
+    for line in luck_summary(probability, attempts, results, attempts as f64 / elapsed) {
+        println!("   • {}", line);
+    }
     
     if !dry_run && results > 0 {
         println!("\n📁 Archivos guardados en: {}", output_dir);